@@ -0,0 +1,45 @@
+use advent_of_code_2019::day16::{fft, fft_fast};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::fs;
+
+fn parse(input: &str) -> Vec<i32> {
+    input
+        .trim()
+        .chars()
+        .map(|c| c.to_digit(10).expect("NaN!") as i32)
+        .collect()
+}
+
+fn real_input(c: &mut Criterion) {
+    let signal = parse(&fs::read_to_string("input/2019/day16.txt").unwrap());
+
+    let mut group = c.benchmark_group("fft 100 phases");
+    group.sample_size(10);
+    group.bench_function("naive", |b| b.iter(|| fft(black_box(&signal), 100)));
+    group.bench_function("prefix sums", |b| {
+        b.iter(|| fft_fast(black_box(&signal), 100))
+    });
+    group.finish();
+}
+
+fn synthetic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fft 1 phase");
+    group.sample_size(10);
+
+    for len in [5_000, 20_000].iter() {
+        let signal: Vec<i32> = (0..*len).map(|i| (i * 7 + i / 3) % 10).collect();
+        group.bench_with_input(BenchmarkId::new("naive", len), &signal, |b, signal| {
+            b.iter(|| fft(signal, 1))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("prefix sums", len),
+            &signal,
+            |b, signal| b.iter(|| fft_fast(signal, 1)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, real_input, synthetic);
+criterion_main!(benches);
@@ -0,0 +1,245 @@
+use crate::runner::Outcome;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Where the known answers of a year are kept
+pub fn path(year: u32) -> PathBuf {
+    PathBuf::from(format!("answers/{}.txt", year))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseAnswersError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for ParseAnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Line {}: expected `dayN partN: answer`, got {:?}",
+            self.line, self.text
+        )
+    }
+}
+
+impl Error for ParseAnswersError {}
+
+/// The expected answer of every day and part, alternatives must give the same
+/// answer as the main solution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u32, u32), String>,
+}
+
+impl Answers {
+    /// One `dayN partN: answer` per line, newlines in answers are written as `\n`
+    /// and the escape of terminal colors as `\e`.
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn parse(input: &str) -> Result<Self, ParseAnswersError> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = || {
+                let (key, answer) = line.split_at(line.find(": ")?);
+                let mut key = key.split_whitespace();
+                let day = key.next()?.strip_prefix("day")?.parse().ok()?;
+                let part = key.next()?.strip_prefix("part")?.parse().ok()?;
+                if key.next().is_some() {
+                    return None;
+                }
+                Some(((day, part), unescape(&answer[2..])))
+            };
+            let (key, answer) = entry().ok_or_else(|| ParseAnswersError {
+                line: i + 1,
+                text: line.to_owned(),
+            })?;
+            answers.insert(key, answer);
+        }
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    pub fn check(&self, outcome: &Outcome) -> Verdict {
+        let expected = match self.get(outcome.day, outcome.part) {
+            Some(expected) => expected,
+            None => return Verdict::Unknown,
+        };
+        match &outcome.answer {
+            Ok(answer) if answer == expected => Verdict::Correct,
+            _ => Verdict::Wrong {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+
+    /// Keeps the answers of the main solutions that didn't fail, returns the
+    /// outcomes that changed the file
+    pub fn record<'a>(&mut self, outcomes: &'a [Outcome]) -> Vec<&'a Outcome> {
+        let mut changed = Vec::new();
+        for outcome in outcomes.iter().filter(|outcome| outcome.name.is_none()) {
+            if let Ok(answer) = &outcome.answer {
+                let key = (outcome.day, outcome.part);
+                if self.answers.get(&key) != Some(answer) {
+                    self.answers.insert(key, answer.clone());
+                    changed.push(outcome);
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ((day, part), answer) in &self.answers {
+            writeln!(f, "day{} part{}: {}", day, part, escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\u{1b}', "\\e")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('e')) => {
+                unescaped.push('\u{1b}');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// Gave another answer or failed
+    Wrong {
+        expected: String,
+    },
+    /// Nothing was recorded for that day and part
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{run_selected, Failure, Selection};
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn outcome(day: u32, name: Option<&'static str>, answer: Result<&str, Failure>) -> Outcome {
+        Outcome {
+            day,
+            part: 1,
+            name,
+            generator: Duration::default(),
+            solver: None,
+            answer: answer.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let answers =
+            Answers::parse("# comment\n\nday1 part2: 42\nday8 part2: \\n#.\\n.\\\\\n").unwrap();
+        assert_eq!(answers.get(1, 2), Some("42"));
+        assert_eq!(answers.get(8, 2), Some("\n#.\n.\\"));
+        let colors = Answers::parse("day11 part2: \\e[40;30m#\\e[0m").unwrap();
+        assert_eq!(colors.get(11, 2), Some("\u{1b}[40;30m#\u{1b}[0m"));
+        assert_eq!(colors.to_string(), "day11 part2: \\e[40;30m#\\e[0m\n");
+        assert_eq!(answers.get(1, 1), None);
+        assert_eq!(Answers::parse(&answers.to_string()), Ok(answers));
+
+        assert_eq!(
+            Answers::parse("day1 part1: 3\nday2: 4"),
+            Err(ParseAnswersError {
+                line: 2,
+                text: String::from("day2: 4")
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_and_record() {
+        let mut answers = Answers::parse("day1 part1: 3\nday2 part1: 5").unwrap();
+        assert_eq!(answers.check(&outcome(1, None, Ok("3"))), Verdict::Correct);
+        assert_eq!(
+            answers.check(&outcome(1, Some("map"), Ok("4"))),
+            Verdict::Wrong {
+                expected: String::from("3")
+            }
+        );
+        assert_eq!(
+            answers.check(&outcome(2, None, Err(Failure::Running(String::new())))),
+            Verdict::Wrong {
+                expected: String::from("5")
+            }
+        );
+        assert_eq!(answers.check(&outcome(3, None, Ok("7"))), Verdict::Unknown);
+
+        let outcomes = vec![
+            outcome(1, None, Ok("3")),
+            outcome(2, None, Err(Failure::Running(String::new()))),
+            outcome(3, None, Ok("7")),
+            outcome(4, Some("alt"), Ok("9")),
+        ];
+        let changed = answers.record(&outcomes);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].day, 3);
+        assert_eq!(
+            answers.to_string(),
+            "day1 part1: 3\nday2 part1: 5\nday3 part1: 7\n"
+        );
+    }
+
+    /// Runs every solution against the real inputs, slow in debug builds:
+    /// `cargo test --release -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_golden_answers() {
+        let answers = Answers::parse(&fs::read_to_string(path(2019)).unwrap()).unwrap();
+        let outcomes = run_selected(&Selection::default(), Path::new("input/2019"), |_| ());
+        let wrong: Vec<String> = outcomes
+            .iter()
+            .filter(|outcome| matches!(answers.check(outcome), Verdict::Wrong { .. }))
+            .map(|outcome| outcome.to_string())
+            .collect();
+        assert!(wrong.is_empty(), "{:#?}", wrong);
+    }
+}
@@ -0,0 +1,65 @@
+use advent_of_code_2019::day15_visualization::{
+    animate_exploration, animate_oxygen, CursesScreen, Screen, TextScreen,
+};
+use advent_of_code_2019::intcode_computer::{parse_input, SIZE};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal};
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str = "usage: day15_visualize [--delay <ms>] [--headless <frames file>] [input file]";
+
+fn animate(program: &[SIZE], screen: &mut impl Screen) -> io::Result<()> {
+    let map = animate_exploration(program, screen)?;
+    animate_oxygen(&map, screen)
+}
+
+fn main() -> io::Result<()> {
+    let mut delay = Duration::from_millis(10);
+    let mut headless = None;
+    let mut input = String::from("input/2019/day15.txt");
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--delay" => match args.next().and_then(|ms| ms.parse().ok()) {
+                Some(ms) => delay = Duration::from_millis(ms),
+                None => {
+                    eprintln!("{}", USAGE);
+                    process::exit(1);
+                }
+            },
+            "--headless" => match args.next() {
+                Some(path) => headless = Some(path),
+                None => {
+                    eprintln!("{}", USAGE);
+                    process::exit(1);
+                }
+            },
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => input = arg,
+        }
+    }
+
+    let program = parse_input(&fs::read_to_string(&input)?);
+
+    // without a terminal curses can't draw anything, so write the frames instead
+    if headless.is_none() && !io::stdout().is_terminal() {
+        headless = Some(String::from("day15_frames.txt"));
+    }
+
+    match headless {
+        Some(path) => {
+            let mut screen = TextScreen::new(BufWriter::new(File::create(&path)?));
+            animate(&program, &mut screen)?;
+            println!("Frames written to {}", path);
+        }
+        None => animate(&program, &mut CursesScreen::new(delay))?,
+    }
+
+    Ok(())
+}
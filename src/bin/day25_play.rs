@@ -0,0 +1,35 @@
+use advent_of_code_2019::day25::{play, Droid};
+use advent_of_code_2019::intcode_loader::load_file;
+use std::env;
+use std::io;
+use std::process;
+
+const USAGE: &str = "usage: day25_play [input file]
+
+Type the commands of the adventure, `map` shows every room seen so far";
+
+fn main() -> io::Result<()> {
+    let input = match env::args().nth(1) {
+        Some(arg) if arg == "--help" || arg == "-h" => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Some(path) => path,
+        None => String::from("input/2019/day25.txt"),
+    };
+
+    let program = match load_file(&input) {
+        Ok(program) if !program.is_empty() => program,
+        Ok(_) => {
+            eprintln!("{} is empty", input);
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{}: {}", input, err);
+            process::exit(1);
+        }
+    };
+
+    let stdin = io::stdin();
+    play(&mut Droid::new(&program), stdin.lock(), io::stdout())
+}
@@ -0,0 +1,78 @@
+use advent_of_code_2019::intcode_computer::{CPU, SIZE};
+use advent_of_code_2019::intcode_debugger::Debugger;
+use advent_of_code_2019::intcode_loader::load_file;
+use std::env;
+use std::io;
+use std::process;
+
+const USAGE: &str =
+    "usage: intcode_debug [--tcp <addr> | --unix <path>] [--input <value>]... <program file>
+
+Loads the program paused on its first instruction and waits for one client of the
+line based debug protocol, on 127.0.0.1:7019 by default";
+
+enum Listen {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(String),
+}
+
+fn usage_error() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn main() -> io::Result<()> {
+    let mut listen = Listen::Tcp(String::from("127.0.0.1:7019"));
+    let mut inputs: Vec<SIZE> = Vec::new();
+    let mut program = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tcp" => listen = Listen::Tcp(args.next().unwrap_or_else(|| usage_error())),
+            #[cfg(unix)]
+            "--unix" => listen = Listen::Unix(args.next().unwrap_or_else(|| usage_error())),
+            "--input" => match args.next().and_then(|value| value.parse().ok()) {
+                Some(value) => inputs.push(value),
+                None => usage_error(),
+            },
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if program.is_none() => program = Some(arg),
+            _ => usage_error(),
+        }
+    }
+
+    let path = program.unwrap_or_else(|| usage_error());
+    let program = match load_file(&path) {
+        Ok(program) if !program.is_empty() => program,
+        Ok(_) => {
+            eprintln!("{} is empty", path);
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            process::exit(1);
+        }
+    };
+
+    let mut cpu = CPU::new(program);
+    cpu.allow_print = false;
+    cpu.input.extend(inputs);
+    let mut debugger = Debugger::new(cpu);
+
+    match listen {
+        Listen::Tcp(addr) => {
+            eprintln!("waiting for a debugger on {}", addr);
+            debugger.serve_tcp(addr)
+        }
+        #[cfg(unix)]
+        Listen::Unix(path) => {
+            eprintln!("waiting for a debugger on {}", path);
+            debugger.serve_unix(path)
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use advent_of_code_2019::scaffold::{scaffold, Template};
+use std::env;
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "usage: new_day [--intcode] <day>
+
+Creates src/dayNN.rs from src/template.rs, or src/template_intcode.rs with --intcode,
+declares it in src/lib.rs, lists it in src/runner.rs and creates an empty
+input/2019/dayN.txt if it's missing";
+
+fn main() {
+    let mut template = Template::Plain;
+    let mut day = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--intcode" => template = Template::Intcode,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => match arg.parse::<u32>() {
+                Ok(n) if day.is_none() => day = Some(n),
+                _ => {
+                    eprintln!("{}", USAGE);
+                    process::exit(1);
+                }
+            },
+        }
+    }
+
+    let day = day.unwrap_or_else(|| {
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    });
+
+    match scaffold(Path::new(env!("CARGO_MANIFEST_DIR")), day, template) {
+        Ok(written) => {
+            for path in written {
+                println!("wrote {}", path.display());
+            }
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
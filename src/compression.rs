@@ -0,0 +1,186 @@
+use std::fmt::Display;
+
+/// Budgets for a decomposition, lengths are measured once the tokens are joined with commas
+#[derive(Clone, Copy, Debug)]
+pub struct Constraints {
+    pub max_functions: usize,
+    pub max_function_len: usize,
+    /// Every call in the main routine is a single character
+    pub max_main_len: usize,
+}
+
+/// A main routine calling functions by index, expanding it gives back the tokens
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decomposition<T> {
+    pub main: Vec<usize>,
+    pub functions: Vec<Vec<T>>,
+}
+
+impl<T: Clone + Display> Decomposition<T> {
+    pub fn expand(&self) -> Vec<T> {
+        self.main
+            .iter()
+            .flat_map(|function| self.functions[*function].iter().cloned())
+            .collect()
+    }
+
+    /// Length of the main routine and of every function
+    pub fn total_len(&self) -> usize {
+        main_len(self.main.len())
+            + self
+                .functions
+                .iter()
+                .map(|function| routine_len(function))
+                .sum::<usize>()
+    }
+}
+
+fn main_len(calls: usize) -> usize {
+    (2 * calls).saturating_sub(1)
+}
+
+pub fn routine_len<T: Display>(tokens: &[T]) -> usize {
+    (tokens.iter().map(|token| token.to_string().len() + 1))
+        .sum::<usize>()
+        .saturating_sub(1)
+}
+
+struct Search<'a, T> {
+    tokens: &'a [T],
+    constraints: Constraints,
+    current: Decomposition<T>,
+    /// Longest total length worth finding
+    limit: usize,
+    found: Vec<Decomposition<T>>,
+}
+
+impl<T: Clone + Display + PartialEq> Search<'_, T> {
+    /// Covers the tokens from `position` with the functions we already have, or with
+    /// a new function starting there. Functions are numbered in the order the main
+    /// routine first calls them so every decomposition is only found once.
+    /// The length only grows deeper down, so branches already over the limit are cut.
+    fn from(&mut self, position: usize) {
+        if self.current.total_len() > self.limit {
+            return;
+        }
+        let remaining = &self.tokens[position..];
+        if remaining.is_empty() {
+            self.found.push(self.current.clone());
+            return;
+        }
+        if main_len(self.current.main.len() + 1) > self.constraints.max_main_len {
+            return;
+        }
+
+        for i in 0..self.current.functions.len() {
+            if remaining.starts_with(&self.current.functions[i]) {
+                let len = self.current.functions[i].len();
+                self.current.main.push(i);
+                self.from(position + len);
+                self.current.main.pop();
+            }
+        }
+
+        if self.current.functions.len() == self.constraints.max_functions {
+            return;
+        }
+        for len in 1..=remaining.len() {
+            let function = &remaining[..len];
+            if routine_len(function) > self.constraints.max_function_len {
+                break;
+            }
+            if self.current.functions.iter().any(|f| f == function) {
+                continue;
+            }
+            self.current.functions.push(function.to_vec());
+            self.current.main.push(self.current.functions.len() - 1);
+            self.from(position + len);
+            self.current.main.pop();
+            self.current.functions.pop();
+        }
+    }
+}
+
+/// Every way to cover the tokens within the constraints that is at most `limit` long
+/// once joined, shortest first
+pub fn decompositions<T: Clone + Display + PartialEq>(
+    tokens: &[T],
+    constraints: Constraints,
+    limit: usize,
+) -> Vec<Decomposition<T>> {
+    let mut search = Search {
+        tokens,
+        constraints,
+        current: Decomposition {
+            main: Vec::new(),
+            functions: Vec::new(),
+        },
+        limit,
+        found: Vec::new(),
+    };
+    search.from(0);
+
+    let mut found = search.found;
+    found.sort_by_key(|decomposition| decomposition.total_len());
+    found
+}
+
+/// The shortest way to cover the tokens within the constraints
+pub fn compress<T: Clone + Display + PartialEq>(
+    tokens: &[T],
+    constraints: Constraints,
+) -> Option<Decomposition<T>> {
+    let limit = constraints.max_main_len + constraints.max_functions * constraints.max_function_len;
+    decompositions(tokens, constraints, limit).first().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONSTRAINTS: Constraints = Constraints {
+        max_functions: 2,
+        max_function_len: 5,
+        max_main_len: 7,
+    };
+
+    #[test]
+    fn test_decompositions() {
+        let tokens = ["N", "E", "N", "E", "S", "S", "N", "E"];
+
+        let found = decompositions(&tokens, CONSTRAINTS, usize::MAX);
+        assert!(!found.is_empty());
+        for decomposition in found.iter() {
+            assert_eq!(decomposition.expand(), tokens);
+            assert!(decomposition.functions.len() <= CONSTRAINTS.max_functions);
+        }
+        assert!(found
+            .windows(2)
+            .all(|pair| pair[0].total_len() <= pair[1].total_len()));
+
+        let limit = found[0].total_len();
+        assert!(decompositions(&tokens, CONSTRAINTS, limit)
+            .iter()
+            .all(|d| d.total_len() == limit));
+        assert!(decompositions(&tokens, CONSTRAINTS, limit - 1).is_empty());
+
+        assert_eq!(compress(&tokens, CONSTRAINTS).as_ref(), found.first());
+        assert_eq!(
+            found[0],
+            Decomposition {
+                main: vec![0, 0, 1, 0],
+                functions: vec![vec!["N", "E"], vec!["S", "S"]],
+            }
+        );
+    }
+
+    #[test]
+    fn test_impossible() {
+        let tokens = ["N", "E", "S", "W", "W", "S", "E", "N"];
+        assert_eq!(compress(&tokens, CONSTRAINTS), None);
+
+        let tokens = [10, 200, 3000];
+        assert_eq!(routine_len(&tokens), 11);
+        assert_eq!(routine_len::<u8>(&[]), 0);
+    }
+}
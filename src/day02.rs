@@ -47,9 +47,9 @@ pub fn part1(input: &[SIZE]) -> SIZE {
 
 #[aoc(day2, part2)]
 pub fn part2(input: &[SIZE]) -> SIZE {
-    let (noun, verb) = find_pair(&input, 19_690_720);
+    let (noun, verb) = find_pair(input, 19_690_720);
 
-    (100 * noun + verb)
+    100 * noun + verb
 }
 
 #[cfg(test)]
@@ -65,7 +65,7 @@ fn part1(input: &str) -> i32 {
     let intersect: HashSet<&Point> = wire_1_h.intersection(&wire_2_h).collect();
     intersect
         .iter()
-        .map(|int| manhattan_dist(Point(0, 0), &int))
+        .map(|int| manhattan_dist(Point(0, 0), int))
         .min()
         .unwrap()
 }
@@ -81,8 +81,8 @@ fn part2(input: &str) -> i32 {
     intersect
         .iter()
         .map(|int| {
-            let wire_1_steps = calculate_steps(&wire_1, &int);
-            let wire_2_steps = calculate_steps(&wire_2, &int);
+            let wire_1_steps = calculate_steps(&wire_1, int);
+            let wire_2_steps = calculate_steps(&wire_2, int);
             wire_1_steps + wire_2_steps
         })
         .min()
@@ -55,7 +55,7 @@ impl OrbitMap {
     }
 
     fn count_orbits(&self, planet: &str) -> i32 {
-        match self.map.get(&planet.to_owned()) {
+        match self.map.get(planet) {
             None => 0,
             Some(orbits) => orbits
                 .iter()
@@ -91,7 +91,7 @@ impl OrbitMap {
 
         let mut current = target;
         while current != start {
-            current = discovered.get(&current.to_owned()).unwrap();
+            current = discovered.get(current).unwrap();
             path.push(current.to_owned());
         }
 
@@ -1,3 +1,4 @@
+use crate::intcode_async::{block_on, join_all, Channel, Machine};
 use crate::intcode_computer::{parse_input, State, CPU, SIZE};
 
 use rayon::prelude::*;
@@ -37,6 +38,34 @@ fn test_phase_setting(code: &[SIZE], phase_setting: Vec<SIZE>) -> SIZE {
     phase_signal
 }
 
+/// Every amplifier is an async machine reading from the channel written by the previous one
+fn test_phase_setting_async(code: &[SIZE], phase_setting: &[SIZE]) -> SIZE {
+    let channels: Vec<Channel> = phase_setting
+        .iter()
+        .map(|phase_setting| {
+            let channel = Channel::new();
+            channel.send(*phase_setting);
+            channel
+        })
+        .collect();
+    channels[0].send(0);
+
+    let amplifiers = channels
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let output = channels[(i + 1) % channels.len()].clone();
+            Machine::new(code.to_owned(), input.clone()).pipe(output)
+        })
+        .collect();
+
+    let signals = block_on(join_all(amplifiers));
+    signals
+        .last()
+        .unwrap()
+        .expect("Last amplifier never sent a signal")
+}
+
 #[aoc(day7, part1)]
 fn part1(input: &[SIZE]) -> SIZE {
     let phase_settings = vec![0, 1, 2, 3, 4];
@@ -59,9 +88,20 @@ fn part2(input: &[SIZE]) -> SIZE {
         .unwrap()
 }
 
+#[aoc(day7, part2, asynchronous)]
+fn part2_async(input: &[SIZE]) -> SIZE {
+    let phase_settings = vec![5, 6, 7, 8, 9];
+
+    permute::permute(phase_settings)
+        .iter()
+        .map(|phase_setting| test_phase_setting_async(input, phase_setting))
+        .max()
+        .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::test_phase_setting;
+    use super::{test_phase_setting, test_phase_setting_async};
 
     #[test]
     fn test_day07_part1() {
@@ -101,6 +141,10 @@ mod tests {
 
         let phase_setting = vec![9, 8, 7, 6, 5];
 
+        assert_eq!(
+            test_phase_setting_async(&input, &phase_setting),
+            139_629_729
+        );
         assert_eq!(test_phase_setting(&input, phase_setting), 139_629_729);
 
         let input = vec![
@@ -111,6 +155,7 @@ mod tests {
 
         let phase_setting = vec![9, 7, 8, 5, 6];
 
+        assert_eq!(test_phase_setting_async(&input, &phase_setting), 18216);
         assert_eq!(test_phase_setting(&input, phase_setting), 18216);
     }
 }
@@ -89,7 +89,7 @@ mod tests {
         assert_eq!(result[0], [1, 2, 3, 4, 5, 6]);
         assert_eq!(result[1], [7, 8, 9, 0, 1, 2]);
 
-        println!("{}", print_image(&result[0], width));
+        println!("{}", print_image(result[0], width));
     }
 
     #[test]
@@ -25,7 +25,7 @@ impl Vector2 {
         let deltay = target.y - self.y;
         let slope = (deltay as f32).atan2(deltax as f32);
 
-        let mut slope = slope * -1.0; // reverse because unit circle is upside down, because 0,0 is top left
+        let mut slope = -slope; // reverse because unit circle is upside down, because 0,0 is top left
         if slope <= std::f32::consts::FRAC_PI_2 {
             // make sure to start at PI/2 (90 degrees)
             slope += 2.0 * std::f32::consts::PI;
@@ -1,5 +1,4 @@
 use crate::intcode_computer::{parse_input, State, CPU, SIZE};
-use ansi_term::Colour::{Black, Red, White};
 use std::collections::HashMap;
 
 #[derive(Hash, PartialEq, Eq, Debug)]
@@ -108,12 +107,12 @@ fn part2(input: &[SIZE]) -> String {
 
     for y in 0..height {
         for x in 0..width {
-            let color: String = match result.get(&Point(x, -y)).unwrap_or(&2) {
-                0 => Black.on(Black).paint("#").to_string(),
-                1 => White.on(Black).paint("#").to_string(),
-                _ => Red.on(Black).paint("#").to_string(),
+            // plain text so the answer can be recorded, unpainted panels are black too
+            let color = match result.get(&Point(x, -y)) {
+                Some(1) => '#',
+                _ => '.',
             };
-            output.push_str(&color);
+            output.push(color);
         }
         output.push('\n');
     }
@@ -133,7 +132,7 @@ mod tests {
         let mut input = String::new();
         file.read_to_string(&mut input)?;
 
-        let result = part1(&generator_input(&input.trim()));
+        let result = part1(&generator_input(input.trim()));
         assert_eq!(result, 2539);
 
         Ok(())
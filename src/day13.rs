@@ -1,11 +1,51 @@
-use crate::intcode_computer::{parse_input, State, CPU, SIZE};
+use crate::intcode_computer::{parse_input, Framebuffer, State, CPU, SIZE};
+use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::ops::Range;
+use std::rc::Rc;
 
 #[aoc_generator(day13)]
 fn generator_input(input: &str) -> Vec<SIZE> {
     parse_input(input)
 }
 
+/// Draws every `x, y, tile` triple on a screen just big enough for all of them
+fn draw(output: &[SIZE]) -> Framebuffer {
+    let triples: Vec<&[SIZE]> = output.chunks_exact(3).filter(|t| t[0] >= 0).collect();
+    let width = triples.iter().map(|t| t[0] + 1).max().unwrap_or(0) as usize;
+    let height = triples.iter().map(|t| t[1] + 1).max().unwrap_or(0) as usize;
+    let mut screen = Framebuffer::new(width, height);
+    for t in triples {
+        screen.tiles[t[1] as usize * width + t[0] as usize] = t[2];
+    }
+    screen
+}
+
+/// The game keeps its screen in its own memory, row by row. Finds it by looking for
+/// the tiles drawn at boot, so a `Framebuffer` can be mapped over it.
+pub fn find_screen(program: &[SIZE]) -> Option<(Range<SIZE>, Framebuffer)> {
+    let mut cpu = CPU::new(program.to_owned());
+    cpu.run();
+    let screen = draw(&cpu.output);
+    if screen.is_empty() {
+        return None;
+    }
+    let start = program
+        .windows(screen.tiles.len())
+        .position(|window| window == &screen.tiles[..])? as SIZE;
+    Some((start..start + screen.len(), screen))
+}
+
+pub fn render(screen: &Framebuffer) -> String {
+    screen.render(|tile| match tile {
+        1 => '#',
+        2 => '=',
+        3 => '-',
+        4 => 'o',
+        _ => ' ',
+    })
+}
+
 #[aoc(day13, part1)]
 fn part1(input: &[SIZE]) -> i32 {
     let mut cpu = CPU::new(input.to_owned());
@@ -15,21 +55,19 @@ fn part1(input: &[SIZE]) -> i32 {
     loop {
         match cpu.step() {
             State::Halt => break,
-            State::Running => {
-                if cpu.output.len() == 3 {
-                    let tile_id = cpu.output[2];
-                    cpu.output.clear();
-
-                    if tile_id == 2 {
-                        block_count += 1;
-                    }
+            State::Running if cpu.output.len() == 3 => {
+                let tile_id = cpu.output[2];
+                cpu.output.clear();
+
+                if tile_id == 2 {
+                    block_count += 1;
                 }
             }
             _ => (),
         }
     }
 
-    block_count as i32
+    block_count
 }
 
 #[aoc(day13, part2)]
@@ -67,3 +105,57 @@ fn part2(input: &[SIZE]) -> SIZE {
 
     score
 }
+
+/// Plays by looking at the mapped screen instead of the drawing instructions
+#[aoc(day13, part2, framebuffer)]
+fn part2_framebuffer(input: &[SIZE]) -> Option<SIZE> {
+    let (region, screen) = find_screen(input)?;
+    let screen = Rc::new(RefCell::new(screen));
+    let mut cpu = CPU::new(input.to_owned());
+    cpu.memory[0] = 2;
+    cpu.map_device(region, screen.clone());
+
+    let column = |tile| {
+        let screen = screen.borrow();
+        let i = screen.tiles.iter().position(|t| *t == tile)?;
+        Some(i % screen.width)
+    };
+    let mut score = 0;
+    loop {
+        let state = cpu.run();
+        for triple in cpu.output.chunks_exact(3) {
+            if triple[0] == -1 {
+                score = triple[2];
+            }
+        }
+        cpu.output.clear();
+        match state {
+            State::Input => {
+                let joystick = match column(3)?.cmp(&column(4)?) {
+                    Ordering::Less => 1,
+                    Ordering::Greater => -1,
+                    Ordering::Equal => 0,
+                };
+                cpu.input.push_back(joystick);
+            }
+            _ => return Some(score),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_screen() {
+        // draws a wall at (0, 0) and a block at (1, 0), the same tiles as at address 19
+        let program = vec![
+            104, 0, 104, 0, 104, 1, 104, 1, 104, 0, 104, 2, 104, -1, 104, 0, 104, 7, 99, 1, 2,
+        ];
+        let (region, screen) = find_screen(&program).unwrap();
+        assert_eq!(region, 19..21);
+        assert_eq!(render(&screen), "#=\n");
+        assert!(find_screen(&[99]).is_none());
+    }
+}
@@ -0,0 +1,391 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+pub const ORE: &str = "ORE";
+pub const FUEL: &str = "FUEL";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chemical {
+    pub name: String,
+    pub quantity: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reaction {
+    pub inputs: Vec<Chemical>,
+    pub output: Chemical,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReactionError {
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    DuplicateOutput(String),
+    /// ORE is a raw material, nothing can produce it
+    ProducesOre,
+    MissingReaction(String),
+    Cycle(String),
+}
+
+impl fmt::Display for ReactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReactionError::Syntax { line, message } => write!(f, "Line {}: {}", line, message),
+            ReactionError::DuplicateOutput(name) => {
+                write!(f, "{} is produced by more than one reaction", name)
+            }
+            ReactionError::ProducesOre => write!(f, "{} can't be produced by a reaction", ORE),
+            ReactionError::MissingReaction(name) => write!(f, "No reaction produces {}", name),
+            ReactionError::Cycle(name) => write!(f, "{} is part of a reaction cycle", name),
+        }
+    }
+}
+
+impl Error for ReactionError {}
+
+fn parse_chemical(text: &str, line: usize) -> Result<Chemical, ReactionError> {
+    let syntax = |message: String| ReactionError::Syntax { line, message };
+
+    let mut parts = text.split_whitespace();
+    let (quantity, name) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(quantity), Some(name), None) => (quantity, name),
+        _ => return Err(syntax(format!("expected `N NAME`, got {:?}", text.trim()))),
+    };
+
+    let quantity = quantity
+        .parse()
+        .map_err(|_| syntax(format!("invalid quantity {:?}", quantity)))?;
+    if quantity == 0 {
+        return Err(syntax(format!("quantity of {} can't be 0", name)));
+    }
+
+    Ok(Chemical {
+        name: name.to_owned(),
+        quantity,
+    })
+}
+
+/// Parses `N A, M B => K C` lines, empty lines are ignored
+pub fn parse_reactions(input: &str) -> Result<Vec<Reaction>, ReactionError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let line_number = i + 1;
+            let mut sides = line.split("=>");
+            match (sides.next(), sides.next(), sides.next()) {
+                (Some(inputs), Some(output), None) => Ok(Reaction {
+                    inputs: inputs
+                        .split(',')
+                        .map(|input| parse_chemical(input, line_number))
+                        .collect::<Result<_, _>>()?,
+                    output: parse_chemical(output, line_number)?,
+                }),
+                _ => Err(ReactionError::Syntax {
+                    line: line_number,
+                    message: String::from("expected exactly one `=>`"),
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Ore consumed and chemicals left over after running every reaction needed for a production
+pub struct Production {
+    pub ore: u64,
+    pub leftovers: HashMap<String, u64>,
+}
+
+pub struct Nanofactory {
+    reactions: HashMap<String, Reaction>,
+    /// Every chemical appears before all the chemicals it's made from, ORE is last
+    order: Vec<String>,
+}
+
+impl Nanofactory {
+    pub fn new(reactions: Vec<Reaction>) -> Result<Self, ReactionError> {
+        let mut by_output = HashMap::new();
+        for reaction in reactions {
+            let name = reaction.output.name.clone();
+            if name == ORE {
+                return Err(ReactionError::ProducesOre);
+            }
+            if by_output.insert(name.clone(), reaction).is_some() {
+                return Err(ReactionError::DuplicateOutput(name));
+            }
+        }
+
+        if !by_output.contains_key(FUEL) {
+            return Err(ReactionError::MissingReaction(String::from(FUEL)));
+        }
+
+        for reaction in by_output.values() {
+            for input in reaction.inputs.iter() {
+                if input.name != ORE && !by_output.contains_key(&input.name) {
+                    return Err(ReactionError::MissingReaction(input.name.clone()));
+                }
+            }
+        }
+
+        let order = topological_order(&by_output)?;
+        Ok(Nanofactory {
+            reactions: by_output,
+            order,
+        })
+    }
+
+    pub fn reactions(&self) -> impl Iterator<Item = &Reaction> {
+        self.reactions.values()
+    }
+
+    pub fn reaction(&self, name: &str) -> Option<&Reaction> {
+        self.reactions.get(name)
+    }
+
+    /// Chemicals ordered from products to raw materials
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Quantity of every chemical consumed or produced to make the target,
+    /// amounts too large for a u64 saturate at `u64::MAX`
+    pub fn demand(&self, target: &str, quantity: u64) -> HashMap<String, u64> {
+        let mut needed: HashMap<String, u64> = HashMap::new();
+        needed.insert(target.to_owned(), quantity);
+
+        // Everything that needs a chemical comes before it in the order,
+        // so its total is known by the time we reach it
+        for name in self.order.iter() {
+            let amount = match needed.get(name) {
+                Some(amount) => *amount,
+                None => continue,
+            };
+            if let Some(reaction) = self.reactions.get(name) {
+                let batches = amount.div_ceil(reaction.output.quantity);
+                for input in reaction.inputs.iter() {
+                    let needed = needed.entry(input.name.clone()).or_insert(0);
+                    *needed = needed.saturating_add(input.quantity.saturating_mul(batches));
+                }
+            }
+        }
+
+        needed
+    }
+
+    pub fn produce(&self, target: &str, quantity: u64) -> Production {
+        let demand = self.demand(target, quantity);
+
+        let leftovers = demand
+            .iter()
+            .filter_map(|(name, amount)| {
+                let output = &self.reactions.get(name)?.output;
+                let batches = amount.div_ceil(output.quantity);
+                let leftover = batches.saturating_mul(output.quantity) - amount;
+                if leftover > 0 {
+                    Some((name.clone(), leftover))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Production {
+            ore: *demand.get(ORE).unwrap_or(&0),
+            leftovers,
+        }
+    }
+
+    pub fn ore_for_fuel(&self, fuel: u64) -> u64 {
+        self.produce(FUEL, fuel).ore
+    }
+
+    /// Binary search on the amount of fuel, producing more fuel never needs less ore
+    pub fn max_fuel(&self, ore: u64) -> u64 {
+        // a saturated amount is more than any budget
+        let enough = |fuel| {
+            let needed = self.ore_for_fuel(fuel);
+            needed <= ore && needed < u64::MAX
+        };
+        if !enough(1) {
+            return 0;
+        }
+
+        // making fuel in bulk reuses leftovers so it's at least this much
+        let mut low = ore / self.ore_for_fuel(1);
+        let mut high = low.saturating_mul(2);
+        while enough(high) {
+            if high == u64::MAX {
+                return high;
+            }
+            low = high;
+            high = high.saturating_mul(2);
+        }
+
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if enough(middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        low
+    }
+}
+
+/// Depth first search from every product, a chemical seen again while it's being visited is a cycle
+fn topological_order(reactions: &HashMap<String, Reaction>) -> Result<Vec<String>, ReactionError> {
+    fn visit<'a>(
+        name: &'a str,
+        reactions: &'a HashMap<String, Reaction>,
+        visiting: &mut HashSet<&'a str>,
+        done: &mut HashSet<&'a str>,
+        order: &mut Vec<String>,
+    ) -> Result<(), ReactionError> {
+        if done.contains(name) {
+            return Ok(());
+        }
+        if !visiting.insert(name) {
+            return Err(ReactionError::Cycle(name.to_owned()));
+        }
+        if let Some(reaction) = reactions.get(name) {
+            for input in reaction.inputs.iter() {
+                visit(&input.name, reactions, visiting, done, order)?;
+            }
+        }
+        visiting.remove(name);
+        done.insert(name);
+        order.push(name.to_owned());
+        Ok(())
+    }
+
+    let mut names: Vec<&String> = reactions.keys().collect();
+    names.sort();
+
+    let mut visiting = HashSet::new();
+    let mut done = HashSet::new();
+    let mut order = Vec::new();
+    for name in names {
+        visit(name, reactions, &mut visiting, &mut done, &mut order)?;
+    }
+
+    order.reverse();
+    Ok(order)
+}
+
+#[aoc_generator(day14)]
+fn generator_input(input: &str) -> Result<Nanofactory, ReactionError> {
+    Nanofactory::new(parse_reactions(input)?)
+}
+
+#[aoc(day14, part1)]
+fn part1(factory: &Nanofactory) -> u64 {
+    factory.ore_for_fuel(1)
+}
+
+#[aoc(day14, part2)]
+fn part2(factory: &Nanofactory) -> u64 {
+    factory.max_fuel(1_000_000_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIMPLE: &str = "10 ORE => 10 A
+1 ORE => 1 B
+7 A, 1 B => 1 C
+7 A, 1 C => 1 D
+7 A, 1 D => 1 E
+7 A, 1 E => 1 FUEL";
+
+    const LARGER: &str = "157 ORE => 5 NZVS
+165 ORE => 6 DCFZ
+44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
+12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
+179 ORE => 7 PSHF
+177 ORE => 5 HKGWZ
+7 DCFZ, 7 PSHF => 2 XJWVT
+165 ORE => 2 GPVTF
+3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT";
+
+    #[test]
+    fn test_day14_part1() {
+        assert_eq!(part1(&generator_input(SIMPLE).unwrap()), 31);
+        assert_eq!(part1(&generator_input(LARGER).unwrap()), 13312);
+
+        let input = "9 ORE => 2 A
+8 ORE => 3 B
+7 ORE => 5 C
+3 A, 4 B => 1 AB
+5 B, 7 C => 1 BC
+4 C, 1 A => 1 CA
+2 AB, 3 BC, 4 CA => 1 FUEL";
+        assert_eq!(part1(&generator_input(input).unwrap()), 165);
+    }
+
+    #[test]
+    fn test_day14_part2() {
+        assert_eq!(part2(&generator_input(LARGER).unwrap()), 82_892_753);
+
+        let factory = generator_input("10 ORE => 1 FUEL").unwrap();
+        assert_eq!(factory.max_fuel(u64::MAX), u64::MAX / 10);
+        let factory = generator_input("1 ORE => 10 FUEL").unwrap();
+        assert_eq!(factory.max_fuel(u64::MAX), u64::MAX);
+        let factory = generator_input(LARGER).unwrap();
+        assert_eq!(factory.ore_for_fuel(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_leftovers() {
+        let factory = generator_input(SIMPLE).unwrap();
+        let production = factory.produce(FUEL, 1);
+
+        assert_eq!(production.ore, 31);
+        assert_eq!(production.leftovers.get("A"), Some(&2));
+        assert_eq!(production.leftovers.get("B"), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse_reactions("10 ORE => 10 A\n7 A, B => 1 FUEL").err(),
+            Some(ReactionError::Syntax {
+                line: 2,
+                message: String::from("expected `N NAME`, got \"B\"")
+            })
+        );
+        assert!(parse_reactions("10 ORE -> 10 A").is_err());
+        assert!(parse_reactions("x ORE => 10 A").is_err());
+
+        let missing = parse_reactions("7 A => 1 FUEL").unwrap();
+        assert_eq!(
+            Nanofactory::new(missing).err(),
+            Some(ReactionError::MissingReaction(String::from("A")))
+        );
+
+        let no_fuel = parse_reactions("10 ORE => 10 A\n7 A => 1 B").unwrap();
+        assert_eq!(
+            Nanofactory::new(no_fuel).err(),
+            Some(ReactionError::MissingReaction(String::from(FUEL)))
+        );
+        assert!(generator_input("").is_err());
+
+        let ore = parse_reactions("1 A => 2 ORE\n1 ORE => 1 A\n1 A => 1 FUEL").unwrap();
+        assert_eq!(
+            Nanofactory::new(ore).err(),
+            Some(ReactionError::ProducesOre)
+        );
+
+        let cycle = parse_reactions("1 B => 1 A\n1 A, 1 ORE => 1 B\n1 A => 1 FUEL").unwrap();
+        assert!(matches!(
+            Nanofactory::new(cycle).err(),
+            Some(ReactionError::Cycle(_))
+        ));
+    }
+}
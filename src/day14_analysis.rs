@@ -0,0 +1,237 @@
+use crate::day14::{Nanofactory, Reaction, FUEL, ORE};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Issue {
+    /// Chemicals in the order they depend on each other, the first one is repeated at the end
+    Cycle(Vec<String>),
+    /// Produced by a reaction but never needed to make FUEL
+    Unreachable(String),
+    /// Consumed by a reaction but nothing produces it
+    Unproducible(String),
+    DuplicateOutput(String),
+}
+
+fn by_output(reactions: &[Reaction]) -> BTreeMap<&str, &Reaction> {
+    reactions
+        .iter()
+        .map(|reaction| (reaction.output.name.as_str(), reaction))
+        .collect()
+}
+
+fn find_cycles<'a>(
+    name: &'a str,
+    graph: &BTreeMap<&'a str, &'a Reaction>,
+    path: &mut Vec<&'a str>,
+    done: &mut BTreeSet<&'a str>,
+    issues: &mut Vec<Issue>,
+) {
+    if let Some(start) = path.iter().position(|chemical| *chemical == name) {
+        let mut cycle: Vec<String> = path[start..].iter().map(|x| x.to_string()).collect();
+        cycle.push(name.to_owned());
+        issues.push(Issue::Cycle(cycle));
+        return;
+    }
+    if !done.insert(name) {
+        return;
+    }
+
+    if let Some(reaction) = graph.get(name) {
+        path.push(name);
+        for input in reaction.inputs.iter() {
+            find_cycles(&input.name, graph, path, done, issues);
+        }
+        path.pop();
+    }
+}
+
+/// Everything that's wrong with the reactions, works on graphs `Nanofactory` would refuse
+pub fn issues(reactions: &[Reaction]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let graph = by_output(reactions);
+
+    let mut seen = BTreeSet::new();
+    for reaction in reactions {
+        if !seen.insert(reaction.output.name.as_str()) {
+            issues.push(Issue::DuplicateOutput(reaction.output.name.clone()));
+        }
+    }
+
+    let consumed: BTreeSet<&str> = reactions
+        .iter()
+        .flat_map(|reaction| reaction.inputs.iter().map(|input| input.name.as_str()))
+        .collect();
+    for name in consumed.iter() {
+        if *name != ORE && !graph.contains_key(name) {
+            issues.push(Issue::Unproducible(name.to_string()));
+        }
+    }
+
+    let mut reachable = BTreeSet::new();
+    let mut stack = vec![FUEL];
+    while let Some(name) = stack.pop() {
+        if reachable.insert(name) {
+            if let Some(reaction) = graph.get(name) {
+                stack.extend(reaction.inputs.iter().map(|input| input.name.as_str()));
+            }
+        }
+    }
+    for name in graph.keys() {
+        if !reachable.contains(name) {
+            issues.push(Issue::Unreachable(name.to_string()));
+        }
+    }
+
+    let mut done = BTreeSet::new();
+    for name in graph.keys() {
+        find_cycles(name, &graph, &mut Vec::new(), &mut done, &mut issues);
+    }
+
+    issues
+}
+
+/// Longest chain of reactions from FUEL down to ORE, those reactions have to run one after the other
+pub fn critical_path(factory: &Nanofactory) -> Vec<String> {
+    // chemicals come before their inputs in the order, so going backward
+    // every input already knows its longest path to ORE
+    let mut longest: HashMap<&str, Vec<String>> = HashMap::new();
+    for name in factory.order().iter().rev() {
+        let path = match factory.reaction(name) {
+            None => vec![name.clone()],
+            Some(reaction) => {
+                let mut path = vec![name.clone()];
+                let deepest = reaction
+                    .inputs
+                    .iter()
+                    .map(|input| &longest[input.name.as_str()])
+                    .rev() // keep the first input on ties
+                    .max_by_key(|path| path.len())
+                    .unwrap();
+                path.extend(deepest.iter().cloned());
+                path
+            }
+        };
+        longest.insert(name, path);
+    }
+
+    longest.remove(FUEL).unwrap_or_default()
+}
+
+/// Total quantity of every chemical consumed to make that much fuel
+pub fn total_demand(factory: &Nanofactory, fuel: u64) -> BTreeMap<String, u64> {
+    let mut demand: BTreeMap<String, u64> = factory.demand(FUEL, fuel).into_iter().collect();
+    demand.remove(FUEL);
+    demand
+}
+
+/// What's left over in the factory after making that much fuel
+pub fn surplus(factory: &Nanofactory, fuel: u64) -> BTreeMap<String, u64> {
+    factory.produce(FUEL, fuel).leftovers.into_iter().collect()
+}
+
+/// Graphviz graph with an edge from every input to its product,
+/// labeled with the quantity consumed and the quantity produced by one reaction
+pub fn to_dot(reactions: &[Reaction]) -> String {
+    let mut dot = String::from("digraph reactions {\n");
+    writeln!(dot, "    \"{}\" [shape=box];", FUEL).unwrap();
+    writeln!(dot, "    \"{}\" [shape=box];", ORE).unwrap();
+
+    for reaction in reactions {
+        for input in reaction.inputs.iter() {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{} => {}\"];",
+                input.name, reaction.output.name, input.quantity, reaction.output.quantity
+            )
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day14::parse_reactions;
+
+    const SIMPLE: &str = "10 ORE => 10 A
+1 ORE => 1 B
+7 A, 1 B => 1 C
+7 A, 1 C => 1 D
+7 A, 1 D => 1 E
+7 A, 1 E => 1 FUEL";
+
+    fn factory(input: &str) -> Nanofactory {
+        Nanofactory::new(parse_reactions(input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_demand_and_surplus() {
+        let factory = factory(SIMPLE);
+
+        let demand = total_demand(&factory, 1);
+        assert_eq!(demand["A"], 28);
+        assert_eq!(demand["B"], 1);
+        assert_eq!(demand["ORE"], 31);
+
+        let surplus = surplus(&factory, 1);
+        assert_eq!(
+            surplus.into_iter().collect::<Vec<_>>(),
+            vec![(String::from("A"), 2)]
+        );
+    }
+
+    #[test]
+    fn test_critical_path() {
+        let factory = factory(SIMPLE);
+        assert_eq!(
+            critical_path(&factory),
+            vec!["FUEL", "E", "D", "C", "A", "ORE"]
+        );
+    }
+
+    #[test]
+    fn test_issues() {
+        assert!(issues(&parse_reactions(SIMPLE).unwrap()).is_empty());
+
+        let malformed = parse_reactions(
+            "1 B => 1 A
+1 A, 1 ORE => 1 B
+1 A, 2 X => 1 FUEL
+3 ORE => 1 Y
+1 ORE => 1 Y",
+        )
+        .unwrap();
+        assert_eq!(
+            issues(&malformed),
+            vec![
+                Issue::DuplicateOutput(String::from("Y")),
+                Issue::Unproducible(String::from("X")),
+                Issue::Unreachable(String::from("Y")),
+                Issue::Cycle(vec![
+                    String::from("A"),
+                    String::from("B"),
+                    String::from("A")
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = to_dot(&parse_reactions("10 ORE => 10 A\n7 A => 1 FUEL").unwrap());
+        assert_eq!(
+            dot,
+            "digraph reactions {
+    \"FUEL\" [shape=box];
+    \"ORE\" [shape=box];
+    \"ORE\" -> \"A\" [label=\"10 => 10\"];
+    \"A\" -> \"FUEL\" [label=\"7 => 1\"];
+}
+"
+        );
+    }
+}
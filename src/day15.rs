@@ -0,0 +1,343 @@
+use crate::intcode_computer::{parse_input, State, CPU, SIZE};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn step(self, direction: Direction) -> Point {
+        match direction {
+            Direction::North => Point {
+                x: self.x,
+                y: self.y - 1,
+            },
+            Direction::South => Point {
+                x: self.x,
+                y: self.y + 1,
+            },
+            Direction::West => Point {
+                x: self.x - 1,
+                y: self.y,
+            },
+            Direction::East => Point {
+                x: self.x + 1,
+                y: self.y,
+            },
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::West,
+    Direction::East,
+];
+
+impl Direction {
+    fn command(self) -> SIZE {
+        match self {
+            Direction::North => 1,
+            Direction::South => 2,
+            Direction::West => 3,
+            Direction::East => 4,
+        }
+    }
+
+    fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+            Direction::East => Direction::West,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Tile {
+    Wall,
+    Open,
+    Oxygen,
+}
+
+/// Every tile discovered by the droid, the droid starts at 0,0
+#[derive(Default, Clone)]
+pub struct Map {
+    pub tiles: HashMap<Point, Tile>,
+}
+
+impl Map {
+    pub const START: Point = Point { x: 0, y: 0 };
+
+    pub fn get(&self, point: Point) -> Option<Tile> {
+        self.tiles.get(&point).copied()
+    }
+
+    pub fn oxygen(&self) -> Option<Point> {
+        self.tiles
+            .iter()
+            .find(|(_, tile)| **tile == Tile::Oxygen)
+            .map(|(point, _)| *point)
+    }
+
+    /// Top left and bottom right corners
+    pub fn bounds(&self) -> (Point, Point) {
+        let xs = self.tiles.keys().map(|point| point.x);
+        let ys = self.tiles.keys().map(|point| point.y);
+        (
+            Point {
+                x: xs.clone().min().unwrap_or(0),
+                y: ys.clone().min().unwrap_or(0),
+            },
+            Point {
+                x: xs.max().unwrap_or(0),
+                y: ys.max().unwrap_or(0),
+            },
+        )
+    }
+
+    /// Breadth first search over every tile that isn't a wall
+    pub fn distances_from(&self, start: Point) -> HashMap<Point, usize> {
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        distances.insert(start, 0);
+        queue.push_back(start);
+
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
+            for direction in DIRECTIONS.iter() {
+                let next = point.step(*direction);
+                match self.get(next) {
+                    Some(Tile::Open) | Some(Tile::Oxygen) if !distances.contains_key(&next) => {
+                        distances.insert(next, distance + 1);
+                        queue.push_back(next);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        distances
+    }
+
+    pub fn shortest_path(&self, from: Point, to: Point) -> Option<usize> {
+        self.distances_from(from).get(&to).copied()
+    }
+
+    /// Minutes for the oxygen to reach every open tile
+    pub fn fill_time(&self) -> Option<usize> {
+        self.distances_from(self.oxygen()?).values().max().copied()
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (min, max) = self.bounds();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let point = Point { x, y };
+                let c = match self.get(point) {
+                    _ if point == Map::START => 'D',
+                    Some(Tile::Wall) => '#',
+                    Some(Tile::Open) => '.',
+                    Some(Tile::Oxygen) => 'O',
+                    None => ' ',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Droid {
+    /// Tries to move and reports the tile in that direction
+    move_to: Box<dyn FnMut(Direction) -> Tile>,
+    position: Point,
+}
+
+impl Droid {
+    pub fn new(move_to: impl FnMut(Direction) -> Tile + 'static) -> Self {
+        Droid {
+            move_to: Box::new(move_to),
+            position: Map::START,
+        }
+    }
+
+    /// The repair droid controlled by the Intcode program
+    pub fn from_program(program: &[SIZE]) -> Self {
+        let mut cpu = CPU::new(program.to_owned());
+        cpu.halt_on_output = true;
+        Droid::new(
+            move |direction| match cpu.run_with_input(Some(direction.command())) {
+                State::Output(0) => Tile::Wall,
+                State::Output(1) => Tile::Open,
+                State::Output(2) => Tile::Oxygen,
+                _ => panic!("Droid didn't report a status"),
+            },
+        )
+    }
+
+    /// The droid only moves if there's no wall
+    fn try_move(&mut self, direction: Direction) -> Tile {
+        let tile = (self.move_to)(direction);
+        if tile != Tile::Wall {
+            self.position = self.position.step(direction);
+        }
+        tile
+    }
+}
+
+/// Depth first search of the whole maze, going back the way it came once a tile has no unknown neighbour.
+/// `on_step` is called after every move with the map so far and the droid position.
+pub fn explore_with(mut droid: Droid, mut on_step: impl FnMut(&Map, Point)) -> Map {
+    let mut map = Map::default();
+    map.tiles.insert(Map::START, Tile::Open);
+    let mut path: Vec<Direction> = Vec::new();
+
+    loop {
+        let unknown = DIRECTIONS
+            .iter()
+            .find(|direction| map.get(droid.position.step(**direction)).is_none());
+
+        match unknown {
+            Some(direction) => {
+                let target = droid.position.step(*direction);
+                let tile = droid.try_move(*direction);
+                map.tiles.insert(target, tile);
+                if tile != Tile::Wall {
+                    path.push(*direction);
+                }
+            }
+            None => match path.pop() {
+                Some(direction) => {
+                    droid.try_move(direction.opposite());
+                }
+                None => break,
+            },
+        }
+
+        on_step(&map, droid.position);
+    }
+
+    map
+}
+
+pub fn explore(program: &[SIZE]) -> Map {
+    explore_with(Droid::from_program(program), |_, _| ())
+}
+
+#[aoc_generator(day15)]
+fn generator_input(input: &str) -> Map {
+    explore(&parse_input(input))
+}
+
+#[aoc(day15, part1)]
+fn part1(map: &Map) -> usize {
+    let oxygen = map.oxygen().expect("Oxygen system not found");
+    map.shortest_path(Map::START, oxygen).unwrap()
+}
+
+#[aoc(day15, part2)]
+fn part2(map: &Map) -> usize {
+    map.fill_time().expect("Oxygen system not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_map(input: &str) -> Map {
+        let mut map = Map::default();
+        for (y, line) in input.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let tile = match c {
+                    '#' => Tile::Wall,
+                    '.' => Tile::Open,
+                    'O' => Tile::Oxygen,
+                    _ => continue,
+                };
+                map.tiles.insert(
+                    Point {
+                        x: x as i32,
+                        y: y as i32,
+                    },
+                    tile,
+                );
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn test_explore_with() {
+        let maze = parse_map(&[" ### ", "#..O#", "#.#.#", "#...#", " ### "].join("\n"));
+        // the droid starts at 1,1 in the maze
+        let offset = |point: Point| Point {
+            x: point.x - 1,
+            y: point.y - 1,
+        };
+
+        let mut position = Point { x: 1, y: 1 };
+        let walls = maze.clone();
+        let droid = Droid::new(move |direction| {
+            let next = position.step(direction);
+            let tile = walls.get(next).unwrap_or(Tile::Wall);
+            if tile != Tile::Wall {
+                position = next;
+            }
+            tile
+        });
+
+        let mut steps = 0;
+        let map = explore_with(droid, |map, droid| {
+            steps += 1;
+            assert_eq!(
+                map.get(droid),
+                maze.get(Point {
+                    x: droid.x + 1,
+                    y: droid.y + 1
+                })
+            );
+        });
+
+        assert_eq!(map.tiles.len(), maze.tiles.len());
+        for (point, tile) in maze.tiles.iter() {
+            assert_eq!(map.get(offset(*point)), Some(*tile), "{:?}", point);
+        }
+        assert_eq!(map.oxygen(), Some(Point { x: 2, y: 0 }));
+        assert_eq!(map.shortest_path(Map::START, Point { x: 2, y: 0 }), Some(2));
+        // every tile is tried once and the droid backs out of every open tile, the start aside
+        let open = maze
+            .tiles
+            .values()
+            .filter(|tile| **tile != Tile::Wall)
+            .count();
+        assert_eq!(steps, maze.tiles.len() - 1 + open - 1);
+    }
+
+    #[test]
+    fn test_day15_part2() {
+        let map = parse_map(&[" ##   ", "#..## ", "#.#..#", "#.O.# ", " ###  "].join("\n"));
+
+        assert_eq!(map.fill_time(), Some(4));
+        assert_eq!(
+            map.shortest_path(Point { x: 1, y: 1 }, map.oxygen().unwrap()),
+            Some(3)
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::day15::{explore_with, Droid, Map, Point, Tile};
+use crate::intcode_computer::SIZE;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// Somewhere to draw the frames of the animation
+pub trait Screen {
+    fn draw(&mut self, frame: &str) -> io::Result<()>;
+}
+
+/// Draws in the terminal, waiting `delay` after every frame
+pub struct CursesScreen {
+    window: pancurses::Window,
+    delay: Duration,
+}
+
+impl CursesScreen {
+    pub fn new(delay: Duration) -> Self {
+        let window = pancurses::initscr();
+        pancurses::noecho();
+        pancurses::curs_set(0);
+        CursesScreen { window, delay }
+    }
+}
+
+impl Screen for CursesScreen {
+    fn draw(&mut self, frame: &str) -> io::Result<()> {
+        self.window.erase();
+        self.window.mvaddstr(0, 0, frame);
+        self.window.refresh();
+        thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+impl Drop for CursesScreen {
+    fn drop(&mut self) {
+        pancurses::endwin();
+    }
+}
+
+/// Headless fallback, every frame is written after a `frame N` line
+pub struct TextScreen<W: Write> {
+    writer: W,
+    frame_count: usize,
+}
+
+impl<W: Write> TextScreen<W> {
+    pub fn new(writer: W) -> Self {
+        TextScreen {
+            writer,
+            frame_count: 0,
+        }
+    }
+}
+
+impl<W: Write> Screen for TextScreen<W> {
+    fn draw(&mut self, frame: &str) -> io::Result<()> {
+        writeln!(self.writer, "frame {}", self.frame_count)?;
+        self.writer.write_all(frame.as_bytes())?;
+        self.frame_count += 1;
+        Ok(())
+    }
+}
+
+/// Same as the map display, but with the droid where it currently is
+/// and every tile that already has oxygen
+fn render(map: &Map, droid: Option<Point>, oxygen: impl Fn(Point) -> bool) -> String {
+    let (min, max) = map.bounds();
+    let mut frame = String::new();
+
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let point = Point { x, y };
+            frame.push(match map.get(point) {
+                _ if Some(point) == droid => 'D',
+                Some(Tile::Wall) => '#',
+                Some(Tile::Oxygen) => 'O',
+                Some(Tile::Open) if oxygen(point) => 'O',
+                Some(Tile::Open) => '.',
+                None => ' ',
+            });
+        }
+        frame.push('\n');
+    }
+
+    frame
+}
+
+/// Draws the map while the droid explores it, returns the complete map
+pub fn animate_exploration(program: &[SIZE], screen: &mut impl Screen) -> io::Result<Map> {
+    let mut result = Ok(());
+    let map = explore_with(Droid::from_program(program), |map, droid| {
+        if result.is_ok() {
+            result = screen.draw(&render(map, Some(droid), |_| false));
+        }
+    });
+    result.map(|_| map)
+}
+
+/// Draws one frame per minute until every open tile has oxygen
+pub fn animate_oxygen(map: &Map, screen: &mut impl Screen) -> io::Result<()> {
+    let oxygen = match map.oxygen() {
+        Some(oxygen) => oxygen,
+        None => return Ok(()),
+    };
+    let distances = map.distances_from(oxygen);
+    let minutes = distances.values().max().copied().unwrap_or(0);
+
+    for minute in 0..=minutes {
+        let frame = render(map, None, |point| {
+            distances
+                .get(&point)
+                .is_some_and(|distance| *distance <= minute)
+        });
+        screen.draw(&format!("{}minute {}\n", frame, minute))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> Map {
+        let mut map = Map::default();
+        for (x, tile) in [Tile::Wall, Tile::Open, Tile::Open, Tile::Oxygen, Tile::Wall]
+            .iter()
+            .enumerate()
+        {
+            map.tiles.insert(Point { x: x as i32, y: 0 }, *tile);
+        }
+        map
+    }
+
+    #[test]
+    fn test_animate_oxygen() {
+        let mut screen = TextScreen::new(Vec::new());
+        animate_oxygen(&map(), &mut screen).unwrap();
+
+        assert_eq!(
+            String::from_utf8(screen.writer).unwrap(),
+            "frame 0\n#..O#\nminute 0\n\
+             frame 1\n#.OO#\nminute 1\n\
+             frame 2\n#OOO#\nminute 2\n"
+        );
+    }
+
+    #[test]
+    fn test_render_droid() {
+        assert_eq!(
+            render(&map(), Some(Point { x: 2, y: 0 }), |_| false),
+            "#.DO#\n"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use rayon::prelude::*;
+use std::error::Error;
+use std::fmt;
+
+const BASE_PATTERN: [i32; 4] = [0, 1, 0, -1];
+const PHASES: usize = 100;
+const REPEAT: usize = 10_000;
+const MESSAGE_LEN: usize = 8;
+
+#[aoc_generator(day16)]
+fn generator_input(input: &str) -> Vec<i32> {
+    input
+        .trim()
+        .chars()
+        .map(|c| c.to_digit(10).expect("NaN!") as i32)
+        .collect()
+}
+
+fn digits_to_string(digits: &[i32]) -> String {
+    digits.iter().map(|digit| digit.to_string()).collect()
+}
+
+/// Straightforward O(n^2) phase, every output digit looks at every input digit
+pub fn phase(signal: &[i32]) -> Vec<i32> {
+    (0..signal.len())
+        .map(|i| {
+            let sum: i32 = signal
+                .iter()
+                .enumerate()
+                .map(|(j, digit)| digit * BASE_PATTERN[((j + 1) / (i + 1)) % 4])
+                .sum();
+            sum.abs() % 10
+        })
+        .collect()
+}
+
+pub fn fft(signal: &[i32], phases: usize) -> Vec<i32> {
+    (0..phases).fold(signal.to_owned(), |signal, _| phase(&signal))
+}
+
+/// Output digit i repeats each pattern value i + 1 times, so its sum is made of runs of
+/// i + 1 consecutive digits that are added or subtracted. With prefix sums every run is O(1),
+/// which makes digit i O(n / (i + 1)) and the whole phase O(n log n).
+/// Output digits don't depend on each other so they are computed in parallel.
+pub fn phase_fast(signal: &[i32]) -> Vec<i32> {
+    let len = signal.len();
+    let mut prefix_sums = vec![0_i64; len + 1];
+    for (i, digit) in signal.iter().enumerate() {
+        prefix_sums[i + 1] = prefix_sums[i] + i64::from(*digit);
+    }
+    let run = |start: usize, run_len: usize| {
+        prefix_sums[(start + run_len).min(len)] - prefix_sums[start.min(len)]
+    };
+
+    (0..len)
+        .into_par_iter()
+        .map(|i| {
+            let run_len = i + 1;
+            let mut sum = 0;
+            // the first run of 1s starts at i because the pattern is shifted by one
+            let mut start = i;
+            while start < len {
+                sum += run(start, run_len);
+                sum -= run(start + 2 * run_len, run_len);
+                start += 4 * run_len;
+            }
+            (sum.abs() % 10) as i32
+        })
+        .collect()
+}
+
+pub fn fft_fast(signal: &[i32], phases: usize) -> Vec<i32> {
+    (0..phases).fold(signal.to_owned(), |signal, _| phase_fast(&signal))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidOffset {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl fmt::Display for InvalidOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Message offset {} must be in the second half of the {} digits signal for the suffix sum shortcut",
+            self.offset, self.len
+        )
+    }
+}
+
+impl Error for InvalidOffset {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SignalTooShort {
+    pub len: usize,
+}
+
+impl fmt::Display for SignalTooShort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The signal has {} digits, the message needs {}",
+            self.len, MESSAGE_LEN
+        )
+    }
+}
+
+impl Error for SignalTooShort {}
+
+/// The first digits of the signal after every phase
+fn first_digits(
+    signal: &[i32],
+    fft: impl Fn(&[i32], usize) -> Vec<i32>,
+) -> Result<String, SignalTooShort> {
+    if signal.len() < MESSAGE_LEN {
+        return Err(SignalTooShort { len: signal.len() });
+    }
+    Ok(digits_to_string(&fft(signal, PHASES)[..MESSAGE_LEN]))
+}
+
+/// In the second half of the signal the pattern is 0 before the digit and 1 after it,
+/// so every digit is the sum of all the digits after it.
+/// Only valid when the message starts in the second half.
+pub fn decode_message(
+    signal: &[i32],
+    repeat: usize,
+    phases: usize,
+) -> Result<String, InvalidOffset> {
+    let len = signal.len() * repeat;
+    let offset = signal
+        .iter()
+        .take(7)
+        .fold(0, |acc, digit| acc * 10 + *digit as usize);
+
+    if offset < len / 2 || offset + MESSAGE_LEN > len {
+        return Err(InvalidOffset { offset, len });
+    }
+
+    let mut tail: Vec<i32> = (offset..len).map(|i| signal[i % signal.len()]).collect();
+    for _ in 0..phases {
+        let mut sum = 0;
+        for digit in tail.iter_mut().rev() {
+            sum = (sum + *digit) % 10;
+            *digit = sum;
+        }
+    }
+
+    Ok(digits_to_string(&tail[..MESSAGE_LEN]))
+}
+
+#[aoc(day16, part1)]
+fn part1(signal: &[i32]) -> Result<String, SignalTooShort> {
+    first_digits(signal, fft)
+}
+
+#[aoc(day16, part1, prefix_sums)]
+fn part1_fast(signal: &[i32]) -> Result<String, SignalTooShort> {
+    first_digits(signal, fft_fast)
+}
+
+#[aoc(day16, part2)]
+fn part2(signal: &[i32]) -> Result<String, InvalidOffset> {
+    decode_message(signal, REPEAT, PHASES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases() {
+        let signal = generator_input("12345678");
+        assert_eq!(digits_to_string(&fft(&signal, 1)), "48226158");
+        assert_eq!(digits_to_string(&fft(&signal, 4)), "01029498");
+    }
+
+    #[test]
+    fn test_day16_part1() {
+        assert_eq!(
+            part1(&generator_input("80871224585914546619083218645595")),
+            Ok(String::from("24176176"))
+        );
+        assert_eq!(
+            part1(&generator_input("19617804207202209144916044189917")),
+            Ok(String::from("73745418"))
+        );
+        assert_eq!(
+            part1(&generator_input("69317163492948606335995924319873")),
+            Ok(String::from("52432133"))
+        );
+    }
+
+    #[test]
+    fn test_phase_fast() {
+        let signal = generator_input("12345678");
+        assert_eq!(digits_to_string(&fft_fast(&signal, 4)), "01029498");
+        assert_eq!(
+            part1_fast(&generator_input("80871224585914546619083218645595")),
+            Ok(String::from("24176176"))
+        );
+
+        let signal: Vec<i32> = (0..997).map(|i| (i * 7 + i / 3) % 10).collect();
+        assert_eq!(fft_fast(&signal, 3), fft(&signal, 3));
+    }
+
+    #[test]
+    fn test_day16_part2() {
+        assert_eq!(
+            part2(&generator_input("03036732577212944063491565474664")),
+            Ok(String::from("84462026"))
+        );
+        assert_eq!(
+            part2(&generator_input("02935109699940807407585447034323")),
+            Ok(String::from("78725270"))
+        );
+        assert_eq!(
+            part2(&generator_input("03081770884921959731165446850517")),
+            Ok(String::from("53553731"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_offset() {
+        let signal = generator_input("00000010000000000000");
+        assert_eq!(
+            decode_message(&signal, 1, 1),
+            Err(InvalidOffset { offset: 1, len: 20 })
+        );
+
+        let signal = generator_input("12345678");
+        assert!(part2(&signal).is_err());
+    }
+
+    #[test]
+    fn test_signal_too_short() {
+        let signal = generator_input("1234567");
+        assert_eq!(part1(&signal), Err(SignalTooShort { len: 7 }));
+        assert_eq!(part1_fast(&[]), Err(SignalTooShort { len: 0 }));
+    }
+
+    #[test]
+    fn test_shortcut_matches_naive() {
+        // offset 14 in a signal of 24 digits
+        let signal = generator_input("000001412345678901234567");
+        let naive = fft(&signal, 4);
+        assert_eq!(
+            decode_message(&signal, 1, 4),
+            Ok(digits_to_string(&naive[14..22]))
+        );
+    }
+}
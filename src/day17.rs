@@ -0,0 +1,264 @@
+use crate::compression::{compress, Constraints, Decomposition};
+use crate::intcode_computer::{parse_input, CPU, SIZE};
+
+const FUNCTION_NAMES: [char; 3] = ['A', 'B', 'C'];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn from(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::Up),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            '>' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+}
+
+/// What the camera sees, the robot is on a scaffold
+pub struct Scaffold {
+    grid: Vec<Vec<char>>,
+}
+
+impl Scaffold {
+    pub fn parse(view: &str) -> Self {
+        Scaffold {
+            grid: view
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.chars().collect())
+                .collect(),
+        }
+    }
+
+    fn get(&self, x: i32, y: i32) -> Option<char> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.grid.get(y as usize)?.get(x as usize).copied()
+    }
+
+    fn is_scaffold(&self, x: i32, y: i32) -> bool {
+        match self.get(x, y) {
+            Some('#') => true,
+            Some(c) => Direction::from(c).is_some(),
+            None => false,
+        }
+    }
+
+    pub fn intersections(&self) -> Vec<(i32, i32)> {
+        let mut intersections = Vec::new();
+        for (y, row) in self.grid.iter().enumerate() {
+            for x in 0..row.len() {
+                let (x, y) = (x as i32, y as i32);
+                if self.is_scaffold(x, y)
+                    && self.is_scaffold(x - 1, y)
+                    && self.is_scaffold(x + 1, y)
+                    && self.is_scaffold(x, y - 1)
+                    && self.is_scaffold(x, y + 1)
+                {
+                    intersections.push((x, y));
+                }
+            }
+        }
+        intersections
+    }
+
+    pub fn alignment_parameters(&self) -> i32 {
+        self.intersections().iter().map(|(x, y)| x * y).sum()
+    }
+
+    fn robot(&self) -> Option<((i32, i32), Direction)> {
+        self.grid.iter().enumerate().find_map(|(y, row)| {
+            row.iter().enumerate().find_map(|(x, c)| {
+                Direction::from(*c).map(|direction| ((x as i32, y as i32), direction))
+            })
+        })
+    }
+
+    /// Goes forward as long as possible, then turns toward the only way to go.
+    /// Every step is a turn followed by a move like `R,8`
+    pub fn path(&self) -> Vec<String> {
+        let ((mut x, mut y), mut direction) = self.robot().expect("No robot on the camera");
+        let mut path = Vec::new();
+
+        loop {
+            let (turn, next_direction) = {
+                let left = direction.turn_left();
+                let right = direction.turn_right();
+                let (lx, ly) = left.offset();
+                let (rx, ry) = right.offset();
+                if self.is_scaffold(x + lx, y + ly) {
+                    ('L', left)
+                } else if self.is_scaffold(x + rx, y + ry) {
+                    ('R', right)
+                } else {
+                    break;
+                }
+            };
+            direction = next_direction;
+
+            let (dx, dy) = direction.offset();
+            let mut steps = 0;
+            while self.is_scaffold(x + dx, y + dy) {
+                x += dx;
+                y += dy;
+                steps += 1;
+            }
+            path.push(format!("{},{}", turn, steps));
+        }
+
+        path
+    }
+}
+
+const CONSTRAINTS: Constraints = Constraints {
+    max_functions: 3,
+    max_function_len: 20,
+    max_main_len: 20,
+};
+
+/// Every routine on its own line, ready to send to the robot
+pub fn to_ascii(routines: &Decomposition<String>) -> String {
+    let main: Vec<String> = routines
+        .main
+        .iter()
+        .map(|function| FUNCTION_NAMES[*function].to_string())
+        .collect();
+    let mut ascii = main.join(",");
+    ascii.push('\n');
+
+    for i in 0..FUNCTION_NAMES.len() {
+        ascii.push_str(
+            &routines
+                .functions
+                .get(i)
+                .map_or(String::new(), |f| f.join(",")),
+        );
+        ascii.push('\n');
+    }
+    ascii
+}
+
+fn camera_view(program: &[SIZE]) -> String {
+    let mut cpu = CPU::new(program.to_owned());
+    cpu.run();
+    cpu.output_ascii()
+}
+
+#[aoc_generator(day17)]
+fn generator_input(input: &str) -> Vec<SIZE> {
+    parse_input(input)
+}
+
+#[aoc(day17, part1)]
+fn part1(input: &[SIZE]) -> i32 {
+    Scaffold::parse(&camera_view(input)).alignment_parameters()
+}
+
+#[aoc(day17, part2)]
+fn part2(input: &[SIZE]) -> SIZE {
+    let scaffold = Scaffold::parse(&camera_view(input));
+    let routines = compress(&scaffold.path(), CONSTRAINTS).expect("Path can't be compressed");
+
+    let mut cpu = CPU::new(input.to_owned());
+    cpu.memory[0] = 2;
+    // no continuous video feed
+    cpu.input_ascii(&(to_ascii(&routines) + "n\n"));
+    cpu.run();
+
+    // the camera view and prompts come first, the dust is the only value that isn't ascii
+    *cpu.output.last().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day17_part1() {
+        let scaffold = Scaffold::parse(
+            "..#..........\n\
+             ..#..........\n\
+             #######...###\n\
+             #.#...#...#.#\n\
+             #############\n\
+             ..#...#...#..\n\
+             ..#####...^..\n",
+        );
+
+        assert_eq!(
+            scaffold.intersections(),
+            vec![(2, 2), (2, 4), (6, 4), (10, 4)]
+        );
+        assert_eq!(scaffold.alignment_parameters(), 76);
+    }
+
+    #[test]
+    fn test_day17_part2() {
+        let scaffold = Scaffold::parse(
+            "#######...#####\n\
+             #.....#...#...#\n\
+             #.....#...#...#\n\
+             ......#...#...#\n\
+             ......#...###.#\n\
+             ......#.....#.#\n\
+             ^########...#.#\n\
+             ......#.#...#.#\n\
+             ......#########\n\
+             ........#...#..\n\
+             ....#########..\n\
+             ....#...#......\n\
+             ....#...#......\n\
+             ....#...#......\n\
+             ....#####......\n",
+        );
+
+        let path = scaffold.path();
+        assert_eq!(
+            path.join(","),
+            "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2"
+        );
+
+        let routines = compress(&path, CONSTRAINTS).unwrap();
+        assert_eq!(routines.expand(), path);
+        for line in to_ascii(&routines).lines() {
+            assert!(line.len() <= 20);
+        }
+    }
+}
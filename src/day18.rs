@@ -0,0 +1,374 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+const KEY_COUNT: usize = 26;
+
+type Keys = u32;
+
+fn key_bit(key: u8) -> Keys {
+    1 << (key - b'a')
+}
+
+fn door_bit(door: u8) -> Keys {
+    1 << (door - b'A')
+}
+
+#[derive(Clone)]
+pub struct Vault {
+    grid: Vec<Vec<u8>>,
+}
+
+/// Shortest way from one point of interest to a key
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    key: usize,
+    distance: usize,
+    /// Doors on the way, we need their keys before going there
+    doors: Keys,
+    /// Keys on the way, they get picked up before reaching that one
+    keys: Keys,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseVaultError {
+    Empty,
+    /// The row isn't as long as the first one
+    Ragged {
+        row: usize,
+    },
+    /// The row isn't closed by walls, counting from 1
+    Unwalled {
+        row: usize,
+    },
+}
+
+impl fmt::Display for ParseVaultError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseVaultError::Empty => write!(f, "The vault map is empty"),
+            ParseVaultError::Ragged { row } => {
+                write!(f, "Row {} doesn't have the length of the first one", row)
+            }
+            ParseVaultError::Unwalled { row } => write!(f, "Row {} isn't closed by walls", row),
+        }
+    }
+}
+
+impl Error for ParseVaultError {}
+
+/// How much work the search did, to spot blowups
+#[derive(Debug)]
+pub struct SearchStats {
+    pub expanded: usize,
+    pub queued: usize,
+    pub elapsed: Duration,
+}
+
+impl Vault {
+    /// The map has to be a rectangle with walls all around
+    pub fn parse(input: &str) -> Result<Self, ParseVaultError> {
+        let grid: Vec<Vec<u8>> = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.bytes().collect())
+            .collect();
+
+        let width = grid.first().ok_or(ParseVaultError::Empty)?.len();
+        for (y, line) in grid.iter().enumerate() {
+            let row = y + 1;
+            if line.len() != width {
+                return Err(ParseVaultError::Ragged { row });
+            }
+            let border = y == 0 || row == grid.len();
+            let walled = if border {
+                line.iter().all(|tile| *tile == b'#')
+            } else {
+                line[0] == b'#' && line[width - 1] == b'#'
+            };
+            if !walled {
+                return Err(ParseVaultError::Unwalled { row });
+            }
+        }
+
+        Ok(Vault { grid })
+    }
+
+    fn find(&self, predicate: impl Fn(u8) -> bool) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if predicate(*tile) {
+                    found.push((x, y));
+                }
+            }
+        }
+        found
+    }
+
+    pub fn entrances(&self) -> Vec<(usize, usize)> {
+        self.find(|tile| tile == b'@')
+    }
+
+    fn all_keys(&self) -> Keys {
+        self.find(|tile| tile.is_ascii_lowercase())
+            .iter()
+            .fold(0, |keys, (x, y)| keys | key_bit(self.grid[*y][*x]))
+    }
+
+    /// Replaces the single entrance with four entrances separated by walls.
+    /// Vaults that already have four entrances are left as they are.
+    pub fn split_quadrants(&self) -> Vault {
+        let mut vault = self.clone();
+        let entrances = self.entrances();
+        if entrances.len() != 1 {
+            return vault;
+        }
+
+        let (x, y) = entrances[0];
+        let center = ["@#@", "###", "@#@"];
+        for (dy, row) in center.iter().enumerate() {
+            for (dx, tile) in row.bytes().enumerate() {
+                vault.grid[y + dy - 1][x + dx - 1] = tile;
+            }
+        }
+        vault
+    }
+
+    /// Breadth first search to every reachable key, walking through doors and keys
+    /// but remembering them
+    fn edges_from(&self, start: (usize, usize)) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        let mut visited: Vec<Vec<bool>> =
+            self.grid.iter().map(|row| vec![false; row.len()]).collect();
+        let mut queue = VecDeque::new();
+        visited[start.1][start.0] = true;
+        queue.push_back((start, 0, 0, 0));
+
+        while let Some(((x, y), distance, doors, keys)) = queue.pop_front() {
+            let tile = self.grid[y][x];
+            let (mut doors, mut keys) = (doors, keys);
+            if tile.is_ascii_uppercase() {
+                doors |= door_bit(tile);
+            }
+            if tile.is_ascii_lowercase() && (x, y) != start {
+                edges.push(Edge {
+                    key: (tile - b'a') as usize,
+                    distance,
+                    doors,
+                    keys,
+                });
+                keys |= key_bit(tile);
+            }
+
+            let neighbours = [
+                x.checked_sub(1).map(|nx| (nx, y)),
+                Some((x + 1, y)),
+                y.checked_sub(1).map(|ny| (x, ny)),
+                Some((x, y + 1)),
+            ];
+            for (nx, ny) in neighbours.iter().flatten() {
+                let open = self
+                    .grid
+                    .get(*ny)
+                    .and_then(|row| row.get(*nx))
+                    .is_some_and(|tile| *tile != b'#');
+                if open && !visited[*ny][*nx] {
+                    visited[*ny][*nx] = true;
+                    queue.push_back(((*nx, *ny), distance + 1, doors, keys));
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Fewest steps for the robots to collect every key, with some stats about the search.
+    /// Nodes are the keys followed by the entrances, the search runs over the node of
+    /// every robot and the keys collected so far.
+    pub fn collect_keys(&self) -> (Option<usize>, SearchStats) {
+        let start = Instant::now();
+        let entrances = self.entrances();
+
+        let mut graph = vec![Vec::new(); KEY_COUNT + entrances.len()];
+        for (x, y) in self.find(|tile| tile.is_ascii_lowercase()) {
+            graph[(self.grid[y][x] - b'a') as usize] = self.edges_from((x, y));
+        }
+        for (i, entrance) in entrances.iter().enumerate() {
+            graph[KEY_COUNT + i] = self.edges_from(*entrance);
+        }
+
+        let all_keys = self.all_keys();
+        let robots: Vec<usize> = (0..entrances.len()).map(|i| KEY_COUNT + i).collect();
+        let mut best: HashMap<(Vec<usize>, Keys), usize> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert((robots.clone(), 0), 0);
+        queue.push(Reverse((0, robots, 0)));
+
+        let mut stats = SearchStats {
+            expanded: 0,
+            queued: 1,
+            elapsed: Duration::default(),
+        };
+        let mut result = None;
+
+        while let Some(Reverse((distance, robots, keys))) = queue.pop() {
+            if keys == all_keys {
+                result = Some(distance);
+                break;
+            }
+            if best[&(robots.clone(), keys)] < distance {
+                continue;
+            }
+            stats.expanded += 1;
+
+            for (robot, node) in robots.iter().enumerate() {
+                for edge in graph[*node].iter() {
+                    let key = 1 << edge.key;
+                    if keys & key != 0 || edge.doors & !keys != 0 || edge.keys & !keys != 0 {
+                        continue;
+                    }
+                    let mut next = robots.clone();
+                    next[robot] = edge.key;
+                    let next_distance = distance + edge.distance;
+                    let state = (next.clone(), keys | key);
+                    if best.get(&state).is_none_or(|d| next_distance < *d) {
+                        best.insert(state, next_distance);
+                        queue.push(Reverse((next_distance, next, keys | key)));
+                        stats.queued += 1;
+                    }
+                }
+            }
+        }
+
+        stats.elapsed = start.elapsed();
+        (result, stats)
+    }
+}
+
+fn report(robots: usize, stats: &SearchStats) {
+    eprintln!(
+        "{} robot(s): {} states expanded, {} queued in {:?}",
+        robots, stats.expanded, stats.queued, stats.elapsed
+    );
+}
+
+#[aoc_generator(day18)]
+fn generator_input(input: &str) -> Result<Vault, ParseVaultError> {
+    Vault::parse(input)
+}
+
+#[aoc(day18, part1)]
+fn part1(vault: &Vault) -> usize {
+    let (steps, stats) = vault.collect_keys();
+    report(1, &stats);
+    steps.expect("Some keys can't be reached")
+}
+
+#[aoc(day18, part2)]
+fn part2(vault: &Vault) -> usize {
+    let vault = vault.split_quadrants();
+    let (steps, stats) = vault.collect_keys();
+    report(vault.entrances().len(), &stats);
+    steps.expect("Some keys can't be reached")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day18_part1() {
+        let vault = Vault::parse(
+            "#########
+             #b.A.@.a#
+             #########",
+        )
+        .unwrap();
+        assert_eq!(part1(&vault), 8);
+
+        let vault = Vault::parse(
+            "########################
+             #f.D.E.e.C.b.A.@.a.B.c.#
+             ######################.#
+             #d.....................#
+             ########################",
+        )
+        .unwrap();
+        assert_eq!(part1(&vault), 86);
+
+        let vault = Vault::parse(
+            "#################
+             #i.G..c...e..H.p#
+             ########.########
+             #j.A..b...f..D.o#
+             ########@########
+             #k.E..a...g..B.n#
+             ########.########
+             #l.F..d...h..C.m#
+             #################",
+        )
+        .unwrap();
+        assert_eq!(part1(&vault), 136);
+    }
+
+    #[test]
+    fn test_day18_part2() {
+        let vault = Vault::parse(
+            "#######
+             #a.#Cd#
+             ##...##
+             ##.@.##
+             ##...##
+             #cB#Ab#
+             #######",
+        )
+        .unwrap();
+        assert_eq!(part2(&vault), 8);
+
+        let vault = Vault::parse(
+            "#############
+             #g#f.D#..h#l#
+             #F###e#E###.#
+             #dCba...BcIJ#
+             #####.@.#####
+             #nK.L...G...#
+             #M###N#H###.#
+             #o#m..#i#jk.#
+             #############",
+        )
+        .unwrap();
+        assert_eq!(part2(&vault), 72);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Vault::parse("").err(), Some(ParseVaultError::Empty));
+        assert_eq!(
+            Vault::parse("#####\n#@.a#\n###").err(),
+            Some(ParseVaultError::Ragged { row: 3 })
+        );
+        assert_eq!(
+            Vault::parse("#####\n@..a#\n#####").err(),
+            Some(ParseVaultError::Unwalled { row: 2 })
+        );
+        assert_eq!(
+            Vault::parse("##.##\n#@.a#\n#####").err(),
+            Some(ParseVaultError::Unwalled { row: 1 })
+        );
+    }
+
+    #[test]
+    fn test_unreachable_key() {
+        let vault = Vault::parse(
+            "#######
+             #@.#.a#
+             #######",
+        )
+        .unwrap();
+        assert_eq!(vault.collect_keys().0, None);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::intcode_computer::{parse_input, CPU, SIZE};
+use std::collections::HashMap;
+
+const AREA: SIZE = 50;
+const SHIP_SIZE: SIZE = 100;
+/// Gives up on rows that are further down than this
+const MAX_ROWS: SIZE = 10_000;
+/// The beam never starts further than this many columns per row from the emitter
+const MAX_SLOPE: SIZE = 10;
+
+/// Remembers every point that was already probed, a probe is a whole Intcode run
+pub struct Beam {
+    probe: Box<dyn Fn(SIZE, SIZE) -> bool>,
+    cache: HashMap<(SIZE, SIZE), bool>,
+    pub probes: usize,
+}
+
+impl Beam {
+    pub fn new(probe: impl Fn(SIZE, SIZE) -> bool + 'static) -> Self {
+        Beam {
+            probe: Box::new(probe),
+            cache: HashMap::new(),
+            probes: 0,
+        }
+    }
+
+    /// A fresh drone for every point
+    pub fn from_program(program: Vec<SIZE>) -> Self {
+        Beam::new(move |x, y| {
+            let mut cpu = CPU::new(program.clone());
+            cpu.input.extend(&[x, y]);
+            cpu.run();
+            cpu.output[0] == 1
+        })
+    }
+
+    pub fn is_pulled(&mut self, x: SIZE, y: SIZE) -> bool {
+        if let Some(pulled) = self.cache.get(&(x, y)) {
+            return *pulled;
+        }
+        self.probes += 1;
+        let pulled = (self.probe)(x, y);
+        self.cache.insert((x, y), pulled);
+        pulled
+    }
+
+    pub fn count_area(&mut self, size: SIZE) -> usize {
+        let mut count = 0;
+        for y in 0..size {
+            for x in 0..size {
+                if self.is_pulled(x, y) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// First and last column of the beam on that row, starting from the edges of the row above.
+    /// Rows close to the emitter can have no beam at all.
+    fn row_edges(&mut self, y: SIZE, above: Option<(SIZE, SIZE)>) -> Option<(SIZE, SIZE)> {
+        let (mut left, above_right) = above.unwrap_or((0, 0));
+        while !self.is_pulled(left, y) {
+            left += 1;
+            if left > (y + 1) * MAX_SLOPE {
+                return None;
+            }
+        }
+
+        let mut right = if above_right > left && self.is_pulled(above_right, y) {
+            above_right
+        } else {
+            left
+        };
+        while self.is_pulled(right + 1, y) {
+            right += 1;
+        }
+        Some((left, right))
+    }
+
+    /// Top left corner of the closest square that fits in the beam. Goes down row by row,
+    /// the bottom left corner is on the left edge and the top right corner has to fit
+    /// before the right edge of the top row.
+    pub fn fit_square(&mut self, size: SIZE) -> Option<(SIZE, SIZE)> {
+        let mut rows: Vec<Option<(SIZE, SIZE)>> = Vec::new();
+        let mut above = None;
+
+        for y in 0..MAX_ROWS {
+            let edges = self.row_edges(y, above);
+            rows.push(edges);
+            if edges.is_some() {
+                above = edges;
+            }
+
+            let (left, _) = match edges {
+                Some(edges) if y + 1 >= size => edges,
+                _ => continue,
+            };
+            let top = y + 1 - size;
+            if let Some((top_left, top_right)) = rows[top as usize] {
+                if top_left <= left && left + size - 1 <= top_right {
+                    return Some((left, top));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[aoc_generator(day19)]
+fn generator_input(input: &str) -> Vec<SIZE> {
+    parse_input(input)
+}
+
+#[aoc(day19, part1)]
+fn part1(input: &[SIZE]) -> usize {
+    Beam::from_program(input.to_owned()).count_area(AREA)
+}
+
+#[aoc(day19, part2)]
+fn part2(input: &[SIZE]) -> SIZE {
+    let (x, y) = Beam::from_program(input.to_owned())
+        .fit_square(SHIP_SIZE)
+        .expect("The ship doesn't fit in the beam");
+    x * 10_000 + y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Between the slopes 4/5 and 3/2, with nothing on the rows right after the emitter
+    fn cone(x: SIZE, y: SIZE) -> bool {
+        (x == 0 && y == 0) || (y >= 4 && 5 * x >= 4 * y && 2 * x <= 3 * y)
+    }
+
+    /// Checks every square of the area, way slower than following the edges
+    fn brute_force(size: SIZE) -> (SIZE, SIZE) {
+        for y in 0.. {
+            for x in 0..2 * y + 1 {
+                let fits = (0..size).all(|dy| (0..size).all(|dx| cone(x + dx, y + dy)));
+                if fits {
+                    return (x, y);
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_count_area() {
+        let mut beam = Beam::new(cone);
+        let expected = (0..10)
+            .flat_map(|y| (0..10).map(move |x| (x, y)))
+            .filter(|(x, y)| cone(*x, *y))
+            .count();
+        assert_eq!(beam.count_area(10), expected);
+
+        let probes = beam.probes;
+        beam.count_area(10);
+        assert_eq!(beam.probes, probes);
+    }
+
+    #[test]
+    fn test_fit_square() {
+        for size in [1, 3, 10].iter() {
+            let mut beam = Beam::new(cone);
+            assert_eq!(beam.fit_square(*size), Some(brute_force(*size)));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+const START: &str = "AA";
+const END: &str = "ZZ";
+/// Deepest level the recursive search goes to before giving up on a path
+const MAX_DEPTH: usize = 200;
+
+type Point = (usize, usize);
+
+/// Open tile next to a label
+#[derive(Debug, Clone)]
+pub struct Portal {
+    pub label: String,
+    pub position: Point,
+    /// On the outside edge of the donut, going through it goes up a level
+    pub outer: bool,
+}
+
+/// Walking distance between two portals, without going through any portal
+#[derive(Debug, Clone, Copy)]
+pub struct Edge {
+    pub to: usize,
+    pub distance: usize,
+}
+
+pub struct Maze {
+    grid: Vec<Vec<u8>>,
+    pub portals: Vec<Portal>,
+}
+
+impl Maze {
+    pub fn parse(input: &str) -> Self {
+        let lines: Vec<&str> = input.lines().collect();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let grid: Vec<Vec<u8>> = lines
+            .iter()
+            .map(|line| {
+                let mut row = line.as_bytes().to_vec();
+                row.resize(width, b' ');
+                row
+            })
+            .collect();
+
+        let height = grid.len();
+        let mut portals = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if grid[y][x] != b'.' {
+                    continue;
+                }
+                // letters are read left to right or top to bottom, whatever side they are on
+                let labels = [
+                    (x >= 2).then(|| [grid[y][x - 2], grid[y][x - 1]]),
+                    (x + 2 < width).then(|| [grid[y][x + 1], grid[y][x + 2]]),
+                    (y >= 2).then(|| [grid[y - 2][x], grid[y - 1][x]]),
+                    (y + 2 < height).then(|| [grid[y + 1][x], grid[y + 2][x]]),
+                ];
+                for label in labels.iter().flatten() {
+                    if label.iter().all(u8::is_ascii_uppercase) {
+                        portals.push(Portal {
+                            label: String::from_utf8(label.to_vec()).unwrap(),
+                            position: (x, y),
+                            outer: x == 2 || y == 2 || x + 3 == width || y + 3 == height,
+                        });
+                    }
+                }
+            }
+        }
+
+        Maze { grid, portals }
+    }
+
+    fn find(&self, label: &str) -> Option<usize> {
+        self.portals.iter().position(|portal| portal.label == label)
+    }
+
+    /// The other side of a portal, AA and ZZ don't have one
+    fn partner(&self, portal: usize) -> Option<usize> {
+        let label = &self.portals[portal].label;
+        (0..self.portals.len())
+            .find(|other| *other != portal && self.portals[*other].label == *label)
+    }
+
+    fn neighbours(&self, (x, y): Point) -> impl Iterator<Item = Point> + '_ {
+        vec![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(move |(x, y)| self.grid[*y][*x] == b'.')
+    }
+
+    /// Breadth first search over the tiles, portals take one step
+    pub fn shortest_path(&self) -> Option<usize> {
+        let start = self.portals[self.find(START)?].position;
+        let end = self.portals[self.find(END)?].position;
+        let warps: HashMap<Point, Point> = (0..self.portals.len())
+            .filter_map(|portal| {
+                let partner = self.partner(portal)?;
+                Some((
+                    self.portals[portal].position,
+                    self.portals[partner].position,
+                ))
+            })
+            .collect();
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(start);
+        queue.push_back((start, 0));
+
+        while let Some((point, distance)) = queue.pop_front() {
+            if point == end {
+                return Some(distance);
+            }
+            for next in self.neighbours(point).chain(warps.get(&point).copied()) {
+                if visited.insert(next) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Edges from every portal to the portals reachable by walking
+    pub fn graph(&self) -> Vec<Vec<Edge>> {
+        let by_position: HashMap<Point, usize> = self
+            .portals
+            .iter()
+            .enumerate()
+            .map(|(i, portal)| (portal.position, i))
+            .collect();
+
+        self.portals
+            .iter()
+            .map(|portal| {
+                let mut edges = Vec::new();
+                let mut visited = HashSet::new();
+                let mut queue = VecDeque::new();
+                visited.insert(portal.position);
+                queue.push_back((portal.position, 0));
+
+                while let Some((point, distance)) = queue.pop_front() {
+                    if let Some(to) = by_position.get(&point) {
+                        if distance > 0 {
+                            edges.push(Edge { to: *to, distance });
+                        }
+                    }
+                    for next in self.neighbours(point) {
+                        if visited.insert(next) {
+                            queue.push_back((next, distance + 1));
+                        }
+                    }
+                }
+                edges
+            })
+            .collect()
+    }
+
+    /// Dijkstra over (portal, level). Inner portals go down a level and outer portals
+    /// go back up, outer portals are walls on the outermost level and AA and ZZ only
+    /// exist there. Levels deeper than `max_depth` are never explored.
+    pub fn recursive_shortest_path(&self, max_depth: usize) -> Option<usize> {
+        let start = self.find(START)?;
+        let end = self.find(END)?;
+        let graph = self.graph();
+        let partners: Vec<Option<usize>> = (0..self.portals.len())
+            .map(|portal| self.partner(portal))
+            .collect();
+
+        let mut best: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert((start, 0), 0);
+        queue.push(Reverse((0, start, 0)));
+
+        while let Some(Reverse((distance, portal, level))) = queue.pop() {
+            if portal == end && level == 0 {
+                return Some(distance);
+            }
+            if best[&(portal, level)] < distance {
+                continue;
+            }
+
+            let mut moves: Vec<(usize, usize, usize)> = graph[portal]
+                .iter()
+                .map(|edge| (edge.to, level, distance + edge.distance))
+                .collect();
+            if let Some(partner) = partners[portal] {
+                let outer = self.portals[portal].outer;
+                if outer && level > 0 {
+                    moves.push((partner, level - 1, distance + 1));
+                } else if !outer && level < max_depth {
+                    moves.push((partner, level + 1, distance + 1));
+                }
+            }
+
+            for (next, next_level, next_distance) in moves {
+                if best
+                    .get(&(next, next_level))
+                    .is_none_or(|d| next_distance < *d)
+                {
+                    best.insert((next, next_level), next_distance);
+                    queue.push(Reverse((next_distance, next, next_level)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[aoc_generator(day20)]
+fn generator_input(input: &str) -> Maze {
+    Maze::parse(input)
+}
+
+#[aoc(day20, part1)]
+fn part1(maze: &Maze) -> usize {
+    maze.shortest_path().expect("No path from AA to ZZ")
+}
+
+#[aoc(day20, part2)]
+fn part2(maze: &Maze) -> usize {
+    maze.recursive_shortest_path(MAX_DEPTH)
+        .expect("No path from AA to ZZ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Maze {
+        Maze::parse(
+            &[
+                "         A           ",
+                "         A           ",
+                "  #######.#########  ",
+                "  #######.........#  ",
+                "  #######.#######.#  ",
+                "  #######.#######.#  ",
+                "  #######.#######.#  ",
+                "  #####  B    ###.#  ",
+                "BC...##  C    ###.#  ",
+                "  ##.##       ###.#  ",
+                "  ##...DE  F  ###.#  ",
+                "  #####    G  ###.#  ",
+                "  #########.#####.#  ",
+                "DE..#######...###.#  ",
+                "  #.#########.###.#  ",
+                "FG..#########.....#  ",
+                "  ###########.#####  ",
+                "             Z       ",
+                "             Z       ",
+            ]
+            .join("\n"),
+        )
+    }
+
+    #[test]
+    fn test_parse_portals() {
+        let maze = example();
+        let mut portals: Vec<(&str, Point, bool)> = maze
+            .portals
+            .iter()
+            .map(|portal| (portal.label.as_str(), portal.position, portal.outer))
+            .collect();
+        portals.sort();
+
+        assert_eq!(
+            portals,
+            vec![
+                ("AA", (9, 2), true),
+                ("BC", (2, 8), true),
+                ("BC", (9, 6), false),
+                ("DE", (2, 13), true),
+                ("DE", (6, 10), false),
+                ("FG", (2, 15), true),
+                ("FG", (11, 12), false),
+                ("ZZ", (13, 16), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_day20_part1() {
+        assert_eq!(part1(&example()), 23);
+    }
+
+    #[test]
+    fn test_day20_part2() {
+        assert_eq!(part2(&example()), 26);
+
+        // the only way to ZZ goes down through BC and back up through DE
+        let maze = Maze::parse(
+            &[
+                "       A   Z       ",
+                "       A   Z       ",
+                "  #####.###.#####  ",
+                "  #####.###.#####  ",
+                "  #####.###.#####  ",
+                "  ###  B   D  ###  ",
+                "  ###  C   E  ###  ",
+                "  ###         ###  ",
+                "  ###############  ",
+                "BC...############  ",
+                "  #.#############  ",
+                "DE..#############  ",
+                "  ###############  ",
+                "                   ",
+                "                   ",
+            ]
+            .join("\n"),
+        );
+        assert_eq!(maze.shortest_path(), Some(10));
+        assert_eq!(maze.recursive_shortest_path(1), Some(10));
+        assert_eq!(maze.recursive_shortest_path(0), None);
+    }
+}
@@ -0,0 +1,518 @@
+use crate::day21_synthesis::{synthesize_with, SynthesisError};
+use crate::intcode_computer::{parse_input, CPU, SIZE};
+use std::error::Error;
+use std::fmt;
+
+pub const MAX_INSTRUCTIONS: usize = 15;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Register {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    T,
+    J,
+}
+
+pub const SENSORS: [Register; 9] = [
+    Register::A,
+    Register::B,
+    Register::C,
+    Register::D,
+    Register::E,
+    Register::F,
+    Register::G,
+    Register::H,
+    Register::I,
+];
+
+impl Register {
+    fn from(c: char) -> Option<Self> {
+        match c {
+            'T' => Some(Register::T),
+            'J' => Some(Register::J),
+            _ => SENSORS.iter().copied().find(|sensor| sensor.name() == c),
+        }
+    }
+
+    fn name(self) -> char {
+        match self {
+            Register::T => 'T',
+            Register::J => 'J',
+            sensor => (b'A' + sensor.distance() as u8 - 1) as char,
+        }
+    }
+
+    /// How many tiles ahead of the droid a sensor looks, 0 for T and J
+    fn distance(self) -> usize {
+        SENSORS
+            .iter()
+            .position(|sensor| *sensor == self)
+            .map_or(0, |i| i + 1)
+    }
+
+    pub fn is_writable(self) -> bool {
+        self == Register::T || self == Register::J
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Op {
+    And,
+    Or,
+    Not,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::Not => "NOT",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Instruction {
+    pub op: Op,
+    pub source: Register,
+    pub target: Register,
+}
+
+/// `WALK` only has the first four sensors, `RUN` has all of them
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Walk,
+    Run,
+}
+
+impl Mode {
+    pub fn sensors(self) -> &'static [Register] {
+        match self {
+            Mode::Walk => &SENSORS[..4],
+            Mode::Run => &SENSORS,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScriptError {
+    Syntax { line: usize, message: String },
+    TooLong(usize),
+    UnavailableSensor(Register),
+    ReadOnlyRegister(Register),
+    MissingMode,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::Syntax { line, message } => write!(f, "Line {}: {}", line, message),
+            ScriptError::TooLong(len) => write!(
+                f,
+                "{} instructions, the droid only has memory for {}",
+                len, MAX_INSTRUCTIONS
+            ),
+            ScriptError::UnavailableSensor(register) => {
+                write!(
+                    f,
+                    "Sensor {} is only available in RUN mode",
+                    register.name()
+                )
+            }
+            ScriptError::ReadOnlyRegister(register) => {
+                write!(f, "Register {} can't be written to", register.name())
+            }
+            ScriptError::MissingMode => write!(f, "The script must end with WALK or RUN"),
+        }
+    }
+}
+
+impl Error for ScriptError {}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Script {
+    pub instructions: Vec<Instruction>,
+    pub mode: Mode,
+}
+
+impl Script {
+    /// Checks everything the droid would refuse
+    pub fn new(instructions: Vec<Instruction>, mode: Mode) -> Result<Self, ScriptError> {
+        if instructions.len() > MAX_INSTRUCTIONS {
+            return Err(ScriptError::TooLong(instructions.len()));
+        }
+        for instruction in instructions.iter() {
+            if !instruction.target.is_writable() {
+                return Err(ScriptError::ReadOnlyRegister(instruction.target));
+            }
+            let source = instruction.source;
+            if !source.is_writable() && !mode.sensors().contains(&source) {
+                return Err(ScriptError::UnavailableSensor(source));
+            }
+        }
+        Ok(Script { instructions, mode })
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ScriptError> {
+        let mut instructions = Vec::new();
+        let mut mode = None;
+        let lines = input
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty());
+
+        for (i, line) in lines {
+            let syntax = |message: &str| ScriptError::Syntax {
+                line: i + 1,
+                message: message.to_owned(),
+            };
+            if mode.is_some() {
+                return Err(syntax("Nothing can come after WALK or RUN"));
+            }
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let op = match tokens[0] {
+                "WALK" if tokens.len() == 1 => {
+                    mode = Some(Mode::Walk);
+                    continue;
+                }
+                "RUN" if tokens.len() == 1 => {
+                    mode = Some(Mode::Run);
+                    continue;
+                }
+                "AND" => Op::And,
+                "OR" => Op::Or,
+                "NOT" => Op::Not,
+                _ => return Err(syntax("Unknown instruction")),
+            };
+            if tokens.len() != 3 {
+                return Err(syntax("Expected two registers"));
+            }
+            let register = |token: &str| {
+                let mut chars = token.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Register::from(c),
+                    _ => None,
+                }
+                .ok_or_else(|| syntax("Unknown register"))
+            };
+            instructions.push(Instruction {
+                op,
+                source: register(tokens[1])?,
+                target: register(tokens[2])?,
+            });
+        }
+
+        match mode {
+            Some(mode) => Script::new(instructions, mode),
+            None => Err(ScriptError::MissingMode),
+        }
+    }
+
+    /// Whether the droid jumps, `ground[i]` is what sensor i sees
+    pub fn evaluate(&self, ground: &[bool; 9]) -> bool {
+        let (mut t, mut j) = (false, false);
+        for instruction in self.instructions.iter() {
+            let source = match instruction.source {
+                Register::T => t,
+                Register::J => j,
+                sensor => ground[sensor.distance() - 1],
+            };
+            let target = if instruction.target == Register::T {
+                &mut t
+            } else {
+                &mut j
+            };
+            *target = match instruction.op {
+                Op::And => *target && source,
+                Op::Or => *target || source,
+                Op::Not => !source,
+            };
+        }
+        j
+    }
+
+    /// Runs the droid over the hull without Intcode, `hull[i]` is true when there's ground.
+    /// The droid starts on the first tile, everything past the end is ground.
+    /// Returns where it fell.
+    pub fn simulate(&self, hull: &[bool]) -> Result<(), usize> {
+        let is_ground = |i: usize| hull.get(i).copied().unwrap_or(true);
+        let mut position = 0;
+
+        while position < hull.len() {
+            let mut ground = [true; 9];
+            for (i, sensor) in ground.iter_mut().enumerate() {
+                *sensor = is_ground(position + i + 1);
+            }
+            position += if self.evaluate(&ground) { 4 } else { 1 };
+            if !is_ground(position) {
+                return Err(position);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The ascii program the droid expects
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for instruction in self.instructions.iter() {
+            writeln!(
+                f,
+                "{} {} {}",
+                instruction.op.name(),
+                instruction.source.name(),
+                instruction.target.name()
+            )?;
+        }
+        match self.mode {
+            Mode::Walk => writeln!(f, "WALK"),
+            Mode::Run => writeln!(f, "RUN"),
+        }
+    }
+}
+
+pub fn parse_hull(hull: &str) -> Vec<bool> {
+    hull.chars().map(|c| c == '#').collect()
+}
+
+pub fn hull_to_string(hull: &[bool]) -> String {
+    hull.iter()
+        .map(|ground| if *ground { '#' } else { '.' })
+        .collect()
+}
+
+/// What the droid shows when it falls in a hole
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fell {
+    pub hull: Vec<bool>,
+    pub frames: Vec<String>,
+}
+
+impl Fell {
+    /// Every frame is made of three rows of air and the hull, the droid starts on
+    /// the left of the first frame and is in the hull once it fell
+    fn decode(output: &str) -> Option<Self> {
+        let animation = output.split("Didn't make it across:").nth(1)?;
+        let frames: Vec<String> = animation
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|frame| !frame.is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        let hull = frames.first()?.lines().last()?;
+        Some(Fell {
+            hull: parse_hull(hull),
+            frames,
+        })
+    }
+}
+
+impl fmt::Display for Fell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "The droid fell in a hole of {}",
+            hull_to_string(&self.hull)
+        )?;
+        for frame in self.frames.iter() {
+            writeln!(f, "{}\n", frame)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Fell {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DroidError {
+    Fell(Fell),
+    /// Neither the hull damage nor a fall, with everything the droid printed
+    UnexpectedOutput(String),
+}
+
+impl fmt::Display for DroidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DroidError::Fell(fell) => write!(f, "{}", fell),
+            DroidError::UnexpectedOutput(output) => {
+                write!(f, "Unexpected output from the droid:\n{}", output)
+            }
+        }
+    }
+}
+
+impl Error for DroidError {}
+
+/// Hull damage reported by the droid
+pub fn run(program: &[SIZE], script: &Script) -> Result<SIZE, DroidError> {
+    let mut cpu = CPU::new(program.to_owned());
+    cpu.input_ascii(&script.to_string());
+    cpu.run();
+
+    match cpu.output.last() {
+        Some(damage) if *damage > 127 => Ok(*damage),
+        _ => {
+            let output = cpu.output_ascii();
+            match Fell::decode(&output) {
+                Some(fell) => Err(DroidError::Fell(fell)),
+                None => Err(DroidError::UnexpectedOutput(output)),
+            }
+        }
+    }
+}
+
+/// Jump when there's a hole in the next three tiles and D can be landed on
+const WALK_SCRIPT: &str = "NOT A J
+NOT B T
+OR T J
+NOT C T
+OR T J
+AND D J
+WALK";
+
+/// Same thing, but only when the droid can either walk one more tile
+/// or jump again right after landing
+const RUN_SCRIPT: &str = "NOT A J
+NOT B T
+OR T J
+NOT C T
+OR T J
+AND D J
+NOT E T
+NOT T T
+OR H T
+AND T J
+RUN";
+
+#[aoc_generator(day21)]
+fn generator_input(input: &str) -> Vec<SIZE> {
+    parse_input(input)
+}
+
+#[aoc(day21, part1)]
+fn part1(input: &[SIZE]) -> Result<SIZE, DroidError> {
+    run(input, &Script::parse(WALK_SCRIPT).unwrap())
+}
+
+#[aoc(day21, part2)]
+fn part2(input: &[SIZE]) -> Result<SIZE, DroidError> {
+    run(input, &Script::parse(RUN_SCRIPT).unwrap())
+}
+
+#[aoc(day21, part1, synthesized)]
+fn part1_synthesized(input: &[SIZE]) -> Result<SIZE, SynthesisError> {
+    synthesize_with(Mode::Walk, |script| run(input, script)).map(|synthesis| synthesis.damage)
+}
+
+#[aoc(day21, part2, synthesized)]
+fn part2_synthesized(input: &[SIZE]) -> Result<SIZE, SynthesisError> {
+    synthesize_with(Mode::Run, |script| run(input, script)).map(|synthesis| synthesis.damage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() {
+        let script = Script::parse(WALK_SCRIPT).unwrap();
+        assert_eq!(script.instructions.len(), 6);
+        assert_eq!(script.mode, Mode::Walk);
+        assert_eq!(script.to_string(), format!("{}\n", WALK_SCRIPT));
+
+        assert_eq!(
+            Script::parse("NOT E J\nWALK"),
+            Err(ScriptError::UnavailableSensor(Register::E))
+        );
+        assert_eq!(
+            Script::parse("NOT J A\nWALK"),
+            Err(ScriptError::ReadOnlyRegister(Register::A))
+        );
+        assert_eq!(Script::parse("NOT A J"), Err(ScriptError::MissingMode));
+        assert_eq!(
+            Script::parse("NOT A J\nWALK\n\nAND D J\n"),
+            Err(ScriptError::Syntax {
+                line: 4,
+                message: String::from("Nothing can come after WALK or RUN")
+            })
+        );
+        assert!(Script::parse("WALK\nRUN").is_err());
+        assert!(Script::parse("NOT A J\nRUN\n\n").is_ok());
+        assert_eq!(
+            Script::parse("NOT A J\nXOR A J\nRUN"),
+            Err(ScriptError::Syntax {
+                line: 2,
+                message: String::from("Unknown instruction")
+            })
+        );
+        assert_eq!(
+            Script::parse(&"NOT A J\n".repeat(16)).map(|_| ()),
+            Err(ScriptError::MissingMode)
+        );
+        assert_eq!(
+            Script::parse(&("NOT A J\n".repeat(16) + "WALK")),
+            Err(ScriptError::TooLong(16))
+        );
+    }
+
+    #[test]
+    fn test_simulate() {
+        let naive = Script::parse("NOT A J\nWALK").unwrap();
+        assert_eq!(naive.simulate(&parse_hull("#####.###########")), Ok(()));
+        assert_eq!(naive.simulate(&parse_hull("#####..#.########")), Err(8));
+
+        let walk = Script::parse(WALK_SCRIPT).unwrap();
+        let run = Script::parse(RUN_SCRIPT).unwrap();
+        for hull in [
+            "#####.###########",
+            "#####..#.########",
+            "#####...#########",
+        ]
+        .iter()
+        {
+            assert_eq!(walk.simulate(&parse_hull(hull)), Ok(()));
+        }
+        assert!(walk.simulate(&parse_hull("#####.#.##..#.###")).is_err());
+        assert_eq!(run.simulate(&parse_hull("#####.#.##..#.###")), Ok(()));
+    }
+
+    #[test]
+    fn test_decode_failure() {
+        let output = "Input instructions:\n\nWalking...\n\nDidn't make it across:\n\n\
+                      .................\n\
+                      .................\n\
+                      @................\n\
+                      #####.###########\n\n\
+                      .................\n\
+                      .................\n\
+                      .....@...........\n\
+                      #####.###########\n\n";
+
+        let fell = Fell::decode(output).unwrap();
+        assert_eq!(hull_to_string(&fell.hull), "#####.###########");
+        assert_eq!(fell.frames.len(), 2);
+        assert!(fell.to_string().contains(".....@..........."));
+    }
+
+    #[test]
+    fn test_unexpected_output() {
+        // prints "Hi" and halts
+        let program = vec![104, 72, 104, 105, 99];
+        let script = Script::parse(WALK_SCRIPT).unwrap();
+        assert_eq!(
+            run(&program, &script),
+            Err(DroidError::UnexpectedOutput(String::from("Hi")))
+        );
+    }
+}
@@ -0,0 +1,495 @@
+use crate::day21::{
+    hull_to_string, DroidError, Instruction, Mode, Op, Register, Script, MAX_INSTRUCTIONS, SENSORS,
+};
+use crate::intcode_computer::SIZE;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// Longest clause tried in the formula
+const MAX_CLAUSE_WIDTH: usize = 3;
+/// Decision assignments tried before giving up on a set of hulls
+const MAX_ASSIGNMENTS: usize = 1_000;
+/// Runs of the real droid before giving up
+const MAX_ROUNDS: usize = 100;
+
+/// What the sensors of a mode see, bit i is set when sensor i sees ground
+type View = u16;
+
+fn view(hull: &[bool], position: usize, sensors: usize) -> View {
+    (0..sensors)
+        .filter(|i| hull.get(position + i + 1).copied().unwrap_or(true))
+        .fold(0, |view, i| view | 1 << i)
+}
+
+/// Whether the droid jumps for every view that matters on the hulls
+type Assignment = HashMap<View, bool>;
+
+/// Walks every hull in order, choosing what to do the first time a view shows up and
+/// backtracking when the droid falls. Walking is tried first so the droid only jumps
+/// when it has to. `found` is called on every assignment that survives every hull,
+/// until it returns true.
+fn assignments(
+    hulls: &[Vec<bool>],
+    sensors: usize,
+    hull: usize,
+    position: usize,
+    assignment: &mut Assignment,
+    found: &mut impl FnMut(&Assignment) -> bool,
+) -> bool {
+    if hull == hulls.len() {
+        return found(assignment);
+    }
+    let tiles = &hulls[hull];
+    if position >= tiles.len() {
+        return assignments(hulls, sensors, hull + 1, 0, assignment, found);
+    }
+
+    let current = view(tiles, position, sensors);
+    let is_ground = |i: usize| tiles.get(i).copied().unwrap_or(true);
+    let mut try_decision = |jump: bool, assignment: &mut Assignment| {
+        let next = position + if jump { 4 } else { 1 };
+        is_ground(next) && assignments(hulls, sensors, hull, next, assignment, found)
+    };
+
+    match assignment.get(&current) {
+        Some(jump) => try_decision(*jump, assignment),
+        None => {
+            for jump in [false, true].iter() {
+                assignment.insert(current, *jump);
+                if try_decision(*jump, assignment) {
+                    return true;
+                }
+            }
+            assignment.remove(&current);
+            false
+        }
+    }
+}
+
+/// A sensor seeing ground, or a hole when it's negated
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Literal {
+    sensor: usize,
+    negated: bool,
+}
+
+impl Literal {
+    fn register(self) -> Register {
+        SENSORS[self.sensor]
+    }
+
+    fn holds(self, view: View) -> bool {
+        ((view >> self.sensor) & 1 == 1) != self.negated
+    }
+}
+
+type Clause = Vec<Literal>;
+
+fn clause_holds(clause: &[Literal], view: View) -> bool {
+    clause.iter().any(|literal| literal.holds(view))
+}
+
+fn instruction(op: Op, source: Register, target: Register) -> Instruction {
+    Instruction { op, source, target }
+}
+
+/// J starts false so the first clause is computed right in J, using T for the negations
+fn compile_first(clause: &[Literal]) -> Vec<Instruction> {
+    let mut sorted = clause.to_vec();
+    sorted.sort_by_key(|literal| !literal.negated);
+
+    let mut instructions = Vec::new();
+    for (i, literal) in sorted.iter().enumerate() {
+        match (i, literal.negated) {
+            (0, true) => instructions.push(instruction(Op::Not, literal.register(), Register::J)),
+            (_, false) => instructions.push(instruction(Op::Or, literal.register(), Register::J)),
+            (_, true) => {
+                instructions.push(instruction(Op::Not, literal.register(), Register::T));
+                instructions.push(instruction(Op::Or, Register::T, Register::J));
+            }
+        }
+    }
+    instructions
+}
+
+/// Every other clause is computed in T then and-ed into J. T can only hold one
+/// negation at a time, so a clause either has at most one negated literal, or is
+/// computed negated when it has at most one literal that isn't.
+fn compile_next(clause: &[Literal]) -> Option<Vec<Instruction>> {
+    let negated = clause.iter().filter(|literal| literal.negated).count();
+    let mut sorted = clause.to_vec();
+    let mut instructions = Vec::new();
+
+    if negated <= 1 {
+        sorted.sort_by_key(|literal| !literal.negated);
+        for (i, literal) in sorted.iter().enumerate() {
+            let register = literal.register();
+            match (i, literal.negated) {
+                (0, true) => instructions.push(instruction(Op::Not, register, Register::T)),
+                (0, false) => {
+                    instructions.push(instruction(Op::Not, register, Register::T));
+                    instructions.push(instruction(Op::Not, Register::T, Register::T));
+                }
+                _ => instructions.push(instruction(Op::Or, register, Register::T)),
+            }
+        }
+        instructions.push(instruction(Op::And, Register::T, Register::J));
+    } else if clause.len() - negated <= 1 {
+        // not (a or not b or not c) is (not a and b and c)
+        sorted.sort_by_key(|literal| literal.negated);
+        for (i, literal) in sorted.iter().enumerate() {
+            let register = literal.register();
+            match (i, literal.negated) {
+                (0, false) => instructions.push(instruction(Op::Not, register, Register::T)),
+                (0, true) => {
+                    instructions.push(instruction(Op::Not, register, Register::T));
+                    instructions.push(instruction(Op::Not, Register::T, Register::T));
+                }
+                _ => instructions.push(instruction(Op::And, register, Register::T)),
+            }
+        }
+        instructions.push(instruction(Op::Not, Register::T, Register::T));
+        instructions.push(instruction(Op::And, Register::T, Register::J));
+    } else {
+        return None;
+    }
+
+    Some(instructions)
+}
+
+/// Jumps when every clause holds, an empty formula always jumps
+fn compile(clauses: &[Clause], mode: Mode) -> Option<Script> {
+    let instructions = match clauses.split_first() {
+        None => vec![instruction(Op::Not, Register::T, Register::J)],
+        Some((first, rest)) => {
+            let mut instructions = compile_first(first);
+            for clause in rest {
+                instructions.extend(compile_next(clause)?);
+            }
+            instructions
+        }
+    };
+    Script::new(instructions, mode).ok()
+}
+
+fn clauses(sensors: usize) -> Vec<Clause> {
+    let literals: Vec<Literal> = (0..sensors)
+        .flat_map(|sensor| {
+            vec![
+                Literal {
+                    sensor,
+                    negated: false,
+                },
+                Literal {
+                    sensor,
+                    negated: true,
+                },
+            ]
+        })
+        .collect();
+
+    let mut clauses: Vec<Clause> = vec![Vec::new()];
+    let mut all = Vec::new();
+    for _ in 0..MAX_CLAUSE_WIDTH {
+        let mut longer = Vec::new();
+        for clause in clauses.iter() {
+            let start = clause.last().map_or(0, |last| last.sensor + 1);
+            for literal in literals.iter().filter(|literal| literal.sensor >= start) {
+                let mut clause = clause.clone();
+                clause.push(*literal);
+                longer.push(clause);
+            }
+        }
+        all.extend(longer.iter().cloned());
+        clauses = longer;
+    }
+    all
+}
+
+struct Cover<'a> {
+    candidates: &'a [(Clause, usize)],
+    walks: &'a [View],
+    mode: Mode,
+    best: Option<Script>,
+}
+
+impl Cover<'_> {
+    /// Depth first search for clauses so that every view where the droid walks
+    /// makes at least one clause false. `budget` is how many instructions are left.
+    fn search(&mut self, chosen: &mut Vec<Clause>, budget: usize) {
+        let uncovered = self
+            .walks
+            .iter()
+            .find(|walk| chosen.iter().all(|clause| clause_holds(clause, **walk)));
+
+        let walk = match uncovered {
+            None => {
+                let script = compile(chosen, self.mode);
+                let shorter = |script: &Script| {
+                    self.best
+                        .as_ref()
+                        .is_none_or(|best| script.instructions.len() < best.instructions.len())
+                };
+                if let Some(script) = script.filter(shorter) {
+                    self.best = Some(script);
+                }
+                return;
+            }
+            Some(walk) => *walk,
+        };
+
+        let budget = match &self.best {
+            Some(best) => budget.min(best.instructions.len().saturating_sub(1)),
+            None => budget,
+        };
+        for (clause, cost) in self.candidates.iter() {
+            if *cost <= budget && !clause_holds(clause, walk) {
+                chosen.push(clause.clone());
+                self.search(chosen, budget - cost);
+                chosen.pop();
+            }
+        }
+    }
+}
+
+/// Smallest script found that jumps exactly on the jump views and walks on the walk
+/// views, whatever it does anywhere else
+fn formula(assignment: &Assignment, mode: Mode) -> Option<Script> {
+    let sensors = mode.sensors().len();
+    let jumps: Vec<View> = assignment
+        .iter()
+        .filter(|(_, jump)| **jump)
+        .map(|(view, _)| *view)
+        .collect();
+    let mut walks: Vec<View> = assignment
+        .iter()
+        .filter(|(_, jump)| !**jump)
+        .map(|(view, _)| *view)
+        .collect();
+    walks.sort_unstable();
+
+    // the clauses have to hold everywhere the droid jumps
+    let candidates: Vec<(Clause, usize)> = clauses(sensors)
+        .into_iter()
+        .filter(|clause| jumps.iter().all(|jump| clause_holds(clause, *jump)))
+        .filter_map(|clause| {
+            let cost = compile_next(&clause)?.len();
+            Some((clause, cost))
+        })
+        .collect();
+    // a clause with fewer literals rules out more walks
+    let dominated = |clause: &Clause| {
+        candidates.iter().any(|(other, _)| {
+            other.len() < clause.len() && other.iter().all(|literal| clause.contains(literal))
+        })
+    };
+    let mut candidates: Vec<(Clause, usize)> = candidates
+        .iter()
+        .filter(|(clause, _)| !dominated(clause))
+        .cloned()
+        .collect();
+    // the ones ruling out the most walks first
+    candidates.sort_by_key(|(clause, cost)| {
+        let ruled_out = walks
+            .iter()
+            .filter(|walk| !clause_holds(clause, **walk))
+            .count();
+        (std::cmp::Reverse(ruled_out), *cost)
+    });
+
+    let mut cover = Cover {
+        candidates: &candidates,
+        walks: &walks,
+        mode,
+        best: None,
+    };
+    // the first clause is at least one instruction shorter once compiled
+    cover.search(&mut Vec::new(), MAX_INSTRUCTIONS + 2);
+    cover.best
+}
+
+/// Shortest script the search finds that gets the droid across every hull
+pub fn synthesize(hulls: &[Vec<bool>], mode: Mode) -> Option<Script> {
+    let sensors = mode.sensors().len();
+    let mut script = None;
+    let mut tries = 0;
+
+    assignments(
+        hulls,
+        sensors,
+        0,
+        0,
+        &mut HashMap::new(),
+        &mut |assignment| {
+            tries += 1;
+            script = formula(assignment, mode)
+                .filter(|script| hulls.iter().all(|hull| script.simulate(hull).is_ok()));
+            script.is_some() || tries >= MAX_ASSIGNMENTS
+        },
+    );
+
+    script
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SynthesisError {
+    /// No script of at most 15 instructions gets across all those hulls
+    NoScript(Vec<Vec<bool>>),
+    /// The droid fell on a hull the local simulator says is fine
+    Mismatch(Vec<bool>),
+    /// The droid printed something that isn't a fall
+    UnexpectedOutput(String),
+    TooManyRounds,
+}
+
+impl fmt::Display for SynthesisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SynthesisError::NoScript(hulls) => {
+                writeln!(f, "No script gets the droid across every hull:")?;
+                for hull in hulls {
+                    writeln!(f, "{}", hull_to_string(hull))?;
+                }
+                Ok(())
+            }
+            SynthesisError::Mismatch(hull) => write!(
+                f,
+                "The droid fell on {} but the simulator says it shouldn't",
+                hull_to_string(hull)
+            ),
+            SynthesisError::UnexpectedOutput(output) => {
+                write!(f, "Unexpected output from the droid:\n{}", output)
+            }
+            SynthesisError::TooManyRounds => {
+                write!(f, "Gave up after {} runs of the droid", MAX_ROUNDS)
+            }
+        }
+    }
+}
+
+impl Error for SynthesisError {}
+
+/// A script that worked and every hull the droid fell on before finding it
+#[derive(Debug)]
+pub struct Synthesis {
+    pub script: Script,
+    pub damage: SIZE,
+    pub hulls: Vec<Vec<bool>>,
+}
+
+/// Synthesizes a script for the hulls seen so far and tries it on the droid,
+/// every hull it falls on is added to the constraints until it makes it across
+pub fn synthesize_with(
+    mode: Mode,
+    mut droid: impl FnMut(&Script) -> Result<SIZE, DroidError>,
+) -> Result<Synthesis, SynthesisError> {
+    let mut hulls = Vec::new();
+
+    for _ in 0..MAX_ROUNDS {
+        let script = match synthesize(&hulls, mode) {
+            Some(script) => script,
+            None => return Err(SynthesisError::NoScript(hulls)),
+        };
+        match droid(&script) {
+            Ok(damage) => {
+                return Ok(Synthesis {
+                    script,
+                    damage,
+                    hulls,
+                })
+            }
+            Err(DroidError::Fell(fell)) if hulls.contains(&fell.hull) => {
+                return Err(SynthesisError::Mismatch(fell.hull))
+            }
+            Err(DroidError::Fell(fell)) => hulls.push(fell.hull),
+            Err(DroidError::UnexpectedOutput(output)) => {
+                return Err(SynthesisError::UnexpectedOutput(output))
+            }
+        }
+    }
+
+    Err(SynthesisError::TooManyRounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day21::{parse_hull, Fell};
+
+    fn parse_hulls(hulls: &[&str]) -> Vec<Vec<bool>> {
+        hulls.iter().map(|hull| parse_hull(hull)).collect()
+    }
+
+    #[test]
+    fn test_compile() {
+        let clauses = vec![
+            vec![
+                Literal {
+                    sensor: 0,
+                    negated: true,
+                },
+                Literal {
+                    sensor: 2,
+                    negated: true,
+                },
+            ],
+            vec![Literal {
+                sensor: 3,
+                negated: false,
+            }],
+        ];
+        assert_eq!(
+            compile(&clauses, Mode::Walk).unwrap().to_string(),
+            "NOT A J\nNOT C T\nOR T J\nNOT D T\nNOT T T\nAND T J\nWALK\n"
+        );
+    }
+
+    #[test]
+    fn test_synthesize() {
+        let hulls = parse_hulls(&[
+            "#####.###########",
+            "#####..#.########",
+            "#####...#########",
+        ]);
+        let script = synthesize(&hulls, Mode::Walk).unwrap();
+        assert!(script.instructions.len() <= MAX_INSTRUCTIONS);
+        for hull in hulls.iter() {
+            assert_eq!(script.simulate(hull), Ok(()));
+        }
+
+        // a hole too long to jump over
+        let hulls = parse_hulls(&["#####.....#######"]);
+        assert_eq!(synthesize(&hulls, Mode::Walk), None);
+    }
+
+    #[test]
+    fn test_synthesize_with_fake_droid() {
+        let hidden = parse_hulls(&[
+            "#####.###########",
+            "#####...#########",
+            "#####..#.########",
+            "#####.#.##..#.###",
+            "#####.##.##...###",
+            "#####..####.#####",
+        ]);
+        let mut runs = 0;
+        let synthesis = synthesize_with(Mode::Run, |script| {
+            runs += 1;
+            match hidden.iter().find(|hull| script.simulate(hull).is_err()) {
+                Some(hull) => Err(DroidError::Fell(Fell {
+                    hull: hull.clone(),
+                    frames: Vec::new(),
+                })),
+                None => Ok(1000),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(synthesis.damage, 1000);
+        assert_eq!(synthesis.hulls.len() + 1, runs);
+        for hull in hidden.iter() {
+            assert_eq!(synthesis.script.simulate(hull), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::error::Error;
+use std::fmt;
+
+const DECK_SIZE: i128 = 10_007;
+const BIG_DECK_SIZE: i128 = 119_315_717_514_047;
+const BIG_SHUFFLES: u64 = 101_741_582_076_661;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Technique {
+    NewStack,
+    Cut(i128),
+    Increment(i128),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseTechniqueError {
+    Empty,
+    Unknown { line: usize, text: String },
+}
+
+impl fmt::Display for ParseTechniqueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTechniqueError::Empty => write!(f, "No shuffle techniques in the input"),
+            ParseTechniqueError::Unknown { line, text } => {
+                write!(f, "Line {}: unknown technique {:?}", line, text)
+            }
+        }
+    }
+}
+
+impl Error for ParseTechniqueError {}
+
+pub fn parse_techniques(input: &str) -> Result<Vec<Technique>, ParseTechniqueError> {
+    let techniques = input
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let number = |prefix: &str| line.strip_prefix(prefix)?.parse().ok();
+            if line == "deal into new stack" {
+                Some(Technique::NewStack)
+            } else if let Some(n) = number("cut ") {
+                Some(Technique::Cut(n))
+            } else {
+                number("deal with increment ").map(Technique::Increment)
+            }
+            .ok_or_else(|| ParseTechniqueError::Unknown {
+                line: i + 1,
+                text: line.to_owned(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if techniques.is_empty() {
+        return Err(ParseTechniqueError::Empty);
+    }
+    Ok(techniques)
+}
+
+/// Where a card ends up is `a * position + b` modulo the deck size,
+/// every shuffle and any chain of shuffles has that form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shuffle {
+    pub a: i128,
+    pub b: i128,
+    pub size: i128,
+}
+
+impl Shuffle {
+    pub fn identity(size: i128) -> Self {
+        Shuffle { a: 1, b: 0, size }
+    }
+
+    pub fn from_technique(technique: Technique, size: i128) -> Self {
+        let (a, b) = match technique {
+            Technique::NewStack => (-1, -1),
+            Technique::Cut(n) => (1, -n),
+            Technique::Increment(n) => (n, 0),
+        };
+        Shuffle {
+            a: a.rem_euclid(size),
+            b: b.rem_euclid(size),
+            size,
+        }
+    }
+
+    pub fn from_techniques(techniques: &[Technique], size: i128) -> Self {
+        techniques
+            .iter()
+            .fold(Shuffle::identity(size), |shuffle, technique| {
+                shuffle.then(Shuffle::from_technique(*technique, size))
+            })
+    }
+
+    /// This shuffle followed by the other one
+    pub fn then(self, other: Shuffle) -> Self {
+        Shuffle {
+            a: (other.a * self.a).rem_euclid(self.size),
+            b: (other.a * self.b + other.b).rem_euclid(self.size),
+            size: self.size,
+        }
+    }
+
+    /// The same shuffle done `times` times in a row, by squaring
+    pub fn repeat(self, mut times: u64) -> Self {
+        let mut result = Shuffle::identity(self.size);
+        let mut square = self;
+        while times > 0 {
+            if times & 1 == 1 {
+                result = result.then(square);
+            }
+            square = square.then(square);
+            times >>= 1;
+        }
+        result
+    }
+
+    /// Undoes the shuffle, only possible when `a` and the deck size are coprime
+    pub fn inverse(self) -> Option<Self> {
+        let a = mod_inverse(self.a, self.size)?;
+        Some(Shuffle {
+            a,
+            b: (-a * self.b).rem_euclid(self.size),
+            size: self.size,
+        })
+    }
+
+    /// Position of the card that started at that position
+    pub fn apply(self, position: i128) -> i128 {
+        (self.a * position + self.b).rem_euclid(self.size)
+    }
+
+    /// Cards in the order they end up in
+    pub fn deal(self) -> Vec<i128> {
+        let mut deck = vec![0; self.size as usize];
+        for card in 0..self.size {
+            deck[self.apply(card) as usize] = card;
+        }
+        deck
+    }
+}
+
+/// Extended Euclidean algorithm
+fn mod_inverse(value: i128, modulus: i128) -> Option<i128> {
+    let (mut old_r, mut r) = (value.rem_euclid(modulus), modulus);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        let (next_r, next_s) = (old_r - quotient * r, old_s - quotient * s);
+        old_r = r;
+        r = next_r;
+        old_s = s;
+        s = next_s;
+    }
+    if old_r == 1 {
+        Some(old_s.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+#[aoc_generator(day22)]
+fn generator_input(input: &str) -> Result<Vec<Technique>, ParseTechniqueError> {
+    parse_techniques(input)
+}
+
+#[aoc(day22, part1)]
+fn part1(techniques: &[Technique]) -> i128 {
+    Shuffle::from_techniques(techniques, DECK_SIZE).apply(2019)
+}
+
+#[aoc(day22, part2)]
+fn part2(techniques: &[Technique]) -> i128 {
+    Shuffle::from_techniques(techniques, BIG_DECK_SIZE)
+        .repeat(BIG_SHUFFLES)
+        .inverse()
+        .expect("The deck size is prime")
+        .apply(2020)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deal(input: &str) -> Vec<i128> {
+        Shuffle::from_techniques(&parse_techniques(input).unwrap(), 10).deal()
+    }
+
+    #[test]
+    fn test_day22_part1() {
+        assert_eq!(
+            deal("deal with increment 7\ndeal into new stack\ndeal into new stack"),
+            vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7]
+        );
+        assert_eq!(
+            deal("cut 6\ndeal with increment 7\ndeal into new stack"),
+            vec![3, 0, 7, 4, 1, 8, 5, 2, 9, 6]
+        );
+        assert_eq!(
+            deal("deal with increment 7\ndeal with increment 9\ncut -2"),
+            vec![6, 3, 0, 7, 4, 1, 8, 5, 2, 9]
+        );
+        assert_eq!(
+            deal(
+                "deal into new stack
+                 cut -2
+                 deal with increment 7
+                 cut 8
+                 cut -4
+                 deal with increment 7
+                 cut 3
+                 deal with increment 9
+                 deal with increment 3
+                 cut -1"
+            ),
+            vec![9, 2, 5, 8, 1, 4, 7, 0, 3, 6]
+        );
+    }
+
+    #[test]
+    fn test_repeat_and_inverse() {
+        let techniques =
+            parse_techniques("cut 6\ndeal with increment 7\ndeal into new stack").unwrap();
+        let shuffle = Shuffle::from_techniques(&techniques, DECK_SIZE);
+
+        let mut position = 2019;
+        for _ in 0..1234 {
+            position = shuffle.apply(position);
+        }
+        assert_eq!(shuffle.repeat(1234).apply(2019), position);
+
+        let inverse = shuffle.repeat(1234).inverse().unwrap();
+        assert_eq!(inverse.apply(position), 2019);
+        assert_eq!(
+            inverse.then(shuffle.repeat(1234)),
+            Shuffle::identity(DECK_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_techniques("cut 3\ndeal with 7"),
+            Err(ParseTechniqueError::Unknown {
+                line: 2,
+                text: String::from("deal with 7")
+            })
+        );
+        assert_eq!(parse_techniques("\n"), Err(ParseTechniqueError::Empty));
+        assert_eq!(
+            Shuffle::from_technique(Technique::Increment(2), 10).inverse(),
+            None
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::intcode_computer::{parse_input, State, CPU, SIZE};
+use std::collections::VecDeque;
+
+const NETWORK_SIZE: usize = 50;
+pub const NAT_ADDRESS: SIZE = 255;
+/// Rounds without any packet before the network counts as idle,
+/// some NICs only send after reading -1 a few times
+const IDLE_ROUNDS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packet {
+    pub destination: SIZE,
+    pub x: SIZE,
+    pub y: SIZE,
+}
+
+/// Every NIC runs in turn, in address order, so the same program always
+/// sends the same packets in the same order
+pub struct Network {
+    nics: Vec<CPU>,
+    queues: Vec<VecDeque<(SIZE, SIZE)>>,
+    /// Values of a packet a NIC hasn't finished sending
+    partial: Vec<Vec<SIZE>>,
+    halted: Vec<bool>,
+    /// Last packet sent to the NAT
+    pub nat: Option<Packet>,
+    quiet_rounds: usize,
+}
+
+impl Network {
+    /// Boots every NIC with its address
+    pub fn new(program: &[SIZE], size: usize) -> Self {
+        let nics = (0..size)
+            .map(|address| {
+                let mut cpu = CPU::new(program.to_owned());
+                cpu.input.push_back(address as SIZE);
+                cpu
+            })
+            .collect();
+
+        Network {
+            nics,
+            queues: vec![VecDeque::new(); size],
+            partial: vec![Vec::new(); size],
+            halted: vec![false; size],
+            nat: None,
+            quiet_rounds: 0,
+        }
+    }
+
+    /// Queues a packet like a NIC would, packets for unknown addresses are dropped
+    pub fn send(&mut self, packet: Packet) {
+        if packet.destination == NAT_ADDRESS {
+            self.nat = Some(packet);
+        } else if let Some(queue) = self.queues.get_mut(packet.destination as usize) {
+            queue.push_back((packet.x, packet.y));
+        }
+    }
+
+    /// Every NIC gets its queued packets, or -1 when there are none, and runs until it
+    /// waits for more input. Returns every packet sent during the round.
+    pub fn round(&mut self) -> Vec<Packet> {
+        let mut sent = Vec::new();
+        let mut quiet = true;
+
+        for address in 0..self.nics.len() {
+            if self.halted[address] {
+                // nothing reads those packets anymore
+                self.queues[address].clear();
+                continue;
+            }
+            let nic = &mut self.nics[address];
+            if self.queues[address].is_empty() {
+                nic.input.push_back(-1);
+            }
+            for (x, y) in self.queues[address].drain(..) {
+                nic.input.extend(&[x, y]);
+            }
+
+            // stops once it needs more input, or halted
+            self.halted[address] = nic.run() == State::Halt;
+            quiet &= nic.output.is_empty();
+            let partial = &mut self.partial[address];
+            partial.append(&mut nic.output);
+            let complete = partial.len() - partial.len() % 3;
+            let output: Vec<SIZE> = partial.drain(..complete).collect();
+
+            for chunk in output.chunks(3) {
+                let packet = Packet {
+                    destination: chunk[0],
+                    x: chunk[1],
+                    y: chunk[2],
+                };
+                self.send(packet);
+                sent.push(packet);
+            }
+        }
+
+        if quiet && self.queues.iter().all(VecDeque::is_empty) {
+            self.quiet_rounds += 1;
+        } else {
+            self.quiet_rounds = 0;
+        }
+        sent
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.quiet_rounds >= IDLE_ROUNDS
+    }
+
+    /// The NAT sends the last packet it got to address 0, returns that packet
+    pub fn wake(&mut self) -> Option<Packet> {
+        let packet = Packet {
+            destination: 0,
+            ..self.nat?
+        };
+        self.send(packet);
+        self.quiet_rounds = 0;
+        Some(packet)
+    }
+
+    /// Runs until a NIC sends something to the NAT, None when the network goes idle first
+    pub fn first_nat_packet(&mut self) -> Option<Packet> {
+        loop {
+            let sent = self.round();
+            if let Some(packet) = sent.iter().find(|p| p.destination == NAT_ADDRESS) {
+                return Some(*packet);
+            }
+            if self.is_idle() {
+                return None;
+            }
+        }
+    }
+
+    /// Runs until the NAT delivers the same Y value twice in a row, None when the
+    /// network goes idle before anything was sent to the NAT
+    pub fn first_repeated_wake(&mut self) -> Option<SIZE> {
+        let mut last_y = None;
+        loop {
+            self.round();
+            if self.is_idle() {
+                let y = self.wake()?.y;
+                if last_y == Some(y) {
+                    return Some(y);
+                }
+                last_y = Some(y);
+            }
+        }
+    }
+}
+
+#[aoc_generator(day23)]
+fn generator_input(input: &str) -> Vec<SIZE> {
+    parse_input(input)
+}
+
+#[aoc(day23, part1)]
+fn part1(input: &[SIZE]) -> SIZE {
+    Network::new(input, NETWORK_SIZE)
+        .first_nat_packet()
+        .expect("Nothing was sent to the NAT")
+        .y
+}
+
+#[aoc(day23, part2)]
+fn part2(input: &[SIZE]) -> SIZE {
+    Network::new(input, NETWORK_SIZE)
+        .first_repeated_wake()
+        .expect("Nothing was sent to the NAT")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Forwards every packet it gets to the next address, the last one sends to the NAT.
+    /// Ignores -1 and keeps x and y as they are.
+    #[rustfmt::skip]
+    const FORWARD: [SIZE; 37] = [
+        3, 100,             // address
+        3, 101,             // x, loops back here
+        1008, 101, -1, 104, // no packet?
+        1005, 104, 2,       // then read again
+        3, 102,             // y
+        1001, 100, 1, 103,  // destination = address + 1
+        1008, 103, 3, 104,  // past the last NIC?
+        1006, 104, 28,
+        1101, 255, 0, 103,  // then it goes to the NAT
+        4, 103,
+        4, 101,
+        4, 102,
+        1105, 1, 2,
+    ];
+
+    fn packet(destination: SIZE, x: SIZE, y: SIZE) -> Packet {
+        Packet { destination, x, y }
+    }
+
+    #[test]
+    fn test_round() {
+        let mut network = Network::new(&FORWARD, 3);
+        assert_eq!(network.round(), vec![]);
+
+        network.send(packet(0, 7, 1));
+        assert_eq!(
+            network.round(),
+            vec![packet(1, 7, 1), packet(2, 7, 1), packet(NAT_ADDRESS, 7, 1)]
+        );
+        assert_eq!(network.nat, Some(packet(NAT_ADDRESS, 7, 1)));
+
+        assert!(!network.is_idle());
+        network.round();
+        network.round();
+        assert!(network.is_idle());
+    }
+
+    #[test]
+    fn test_partial_packet() {
+        #[rustfmt::skip]
+        let program = vec![
+            3, 100,
+            104, 255, 104, 5, // destination and x
+            3, 101, 3, 101,   // waits on the second read
+            104, 6,           // y
+            99,
+        ];
+        let mut network = Network::new(&program, 1);
+        assert_eq!(network.round(), vec![]);
+        assert!(!network.is_idle());
+        assert_eq!(network.round(), vec![packet(NAT_ADDRESS, 5, 6)]);
+
+        // the NIC halted, it never runs again
+        assert!(network.halted[0]);
+        network.send(packet(0, 1, 2));
+        assert_eq!(network.round(), vec![]);
+        assert!(network.nics[0].input.is_empty());
+        network.round();
+        assert!(network.is_idle());
+    }
+
+    #[test]
+    fn test_nat() {
+        let mut network = Network::new(&FORWARD, 3);
+        network.send(packet(0, 7, 1));
+        assert_eq!(network.first_nat_packet(), Some(packet(NAT_ADDRESS, 7, 1)));
+        assert_eq!(network.first_repeated_wake(), Some(1));
+
+        let mut network = Network::new(&FORWARD, 3);
+        assert_eq!(network.first_repeated_wake(), None);
+
+        let mut network = Network::new(&FORWARD, 3);
+        assert_eq!(network.first_nat_packet(), None);
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+const SIZE: usize = 5;
+const TILES: usize = SIZE * SIZE;
+const CENTER: usize = TILES / 2;
+const MINUTES: usize = 200;
+/// Tiles next to the center of a recursive grid have the most, 3 around them and 5 inside
+const MAX_NEIGHBOURS: u32 = 8;
+
+fn bit(x: usize, y: usize) -> u32 {
+    1 << (y * SIZE + x)
+}
+
+/// Which neighbour counts keep a bug alive and which ones make a new bug appear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    survive: u16,
+    birth: u16,
+}
+
+impl Rules {
+    /// A bug survives with exactly one bug next to it, an empty tile
+    /// gets infested with one or two
+    pub const DISCORD: Rules = Rules {
+        survive: 1 << 1,
+        birth: 1 << 1 | 1 << 2,
+    };
+
+    pub fn new(survive: &[u32], birth: &[u32]) -> Result<Self, ParseRulesError> {
+        let mask = |counts: &[u32]| {
+            counts.iter().try_fold(0, |mask, count| match count {
+                0..=MAX_NEIGHBOURS => Ok(mask | 1 << count),
+                _ => Err(ParseRulesError::TooManyNeighbours(*count)),
+            })
+        };
+        Ok(Rules {
+            survive: mask(survive)?,
+            birth: mask(birth)?,
+        })
+    }
+
+    fn is_alive(self, alive: bool, neighbours: u32) -> bool {
+        let counts = if alive { self.survive } else { self.birth };
+        counts >> neighbours & 1 == 1
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseRulesError {
+    TooManyNeighbours(u32),
+    Syntax(String),
+}
+
+impl fmt::Display for ParseRulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRulesError::TooManyNeighbours(count) => write!(
+                f,
+                "A tile has at most {} neighbours, not {}",
+                MAX_NEIGHBOURS, count
+            ),
+            ParseRulesError::Syntax(text) => {
+                write!(f, "Expected rules like B1,2/S1, got {:?}", text)
+            }
+        }
+    }
+}
+
+impl Error for ParseRulesError {}
+
+/// `B` then the counts that give birth, `/S` then the counts to survive, like `B1,2/S1`
+impl FromStr for Rules {
+    type Err = ParseRulesError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let syntax = || ParseRulesError::Syntax(text.to_owned());
+        let counts = |list: &str| -> Result<Vec<u32>, ParseRulesError> {
+            list.split(',')
+                .filter(|count| !count.is_empty())
+                .map(|count| count.trim().parse().map_err(|_| syntax()))
+                .collect()
+        };
+
+        let (birth, survive) = text.trim().split_once('/').ok_or_else(syntax)?;
+        let birth = birth.strip_prefix('B').ok_or_else(syntax)?;
+        let survive = survive.strip_prefix('S').ok_or_else(syntax)?;
+        Rules::new(&counts(survive)?, &counts(birth)?)
+    }
+}
+
+/// Bit `y * 5 + x` is set when there's a bug on that tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Grid(pub u32);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseGridError;
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expected a 5x5 grid of '#' and '.'")
+    }
+}
+
+impl Error for ParseGridError {}
+
+impl Grid {
+    pub fn parse(input: &str) -> Result<Self, ParseGridError> {
+        let rows: Vec<&str> = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != SIZE {
+            return Err(ParseGridError);
+        }
+
+        let mut grid = 0;
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != SIZE {
+                return Err(ParseGridError);
+            }
+            for (x, tile) in row.chars().enumerate() {
+                match tile {
+                    '#' => grid |= bit(x, y),
+                    '.' => (),
+                    _ => return Err(ParseGridError),
+                }
+            }
+        }
+        Ok(Grid(grid))
+    }
+
+    /// Every tile is worth twice as much as the one before it, which is the bitmask itself
+    pub fn biodiversity(self) -> u32 {
+        self.0
+    }
+
+    pub fn bugs(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn step(self, rules: Rules) -> Self {
+        let masks = neighbour_masks();
+        let mut next = 0;
+        for (tile, mask) in masks.iter().enumerate() {
+            let alive = self.0 >> tile & 1 == 1;
+            if rules.is_alive(alive, (self.0 & mask.same).count_ones()) {
+                next |= 1 << tile;
+            }
+        }
+        Grid(next)
+    }
+
+    pub fn first_repeated(self, rules: Rules) -> Self {
+        let mut seen = HashSet::new();
+        let mut grid = self;
+        while seen.insert(grid) {
+            grid = grid.step(rules);
+        }
+        grid
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                write!(f, "{}", if self.0 & bit(x, y) != 0 { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Neighbours of a tile on its own level, on the level around it and on the level inside it
+#[derive(Default, Clone, Copy)]
+struct NeighbourMask {
+    same: u32,
+    outer: u32,
+    inner: u32,
+}
+
+fn neighbour_masks() -> [NeighbourMask; TILES] {
+    let mut masks = [NeighbourMask::default(); TILES];
+    let middle = SIZE / 2;
+
+    for (tile, mask) in masks.iter_mut().enumerate() {
+        let (x, y) = ((tile % SIZE) as i32, (tile / SIZE) as i32);
+        for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)].iter() {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= SIZE as i32 || ny >= SIZE as i32 {
+                // the tile next to the center of the level around
+                mask.outer |= bit((middle as i32 + dx) as usize, (middle as i32 + dy) as usize);
+                continue;
+            }
+            let neighbour = ny as usize * SIZE + nx as usize;
+            mask.same |= 1 << neighbour;
+            if neighbour == CENTER {
+                // the whole edge of the level inside that faces this tile
+                for i in 0..SIZE {
+                    mask.inner |= match (dx, dy) {
+                        (0, 1) => bit(i, 0),
+                        (0, -1) => bit(i, SIZE - 1),
+                        (1, 0) => bit(0, i),
+                        _ => bit(SIZE - 1, i),
+                    };
+                }
+            }
+        }
+    }
+    masks
+}
+
+/// Every level of the recursive grid that has bugs, the center tile of
+/// each level is the level deeper inside
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecursiveGrid {
+    levels: BTreeMap<i32, u32>,
+}
+
+impl RecursiveGrid {
+    pub fn new(grid: Grid) -> Self {
+        let mut levels = BTreeMap::new();
+        levels.insert(0, grid.0 & !(1 << CENTER));
+        RecursiveGrid { levels }
+    }
+
+    pub fn level(&self, depth: i32) -> Grid {
+        Grid(self.levels.get(&depth).copied().unwrap_or(0))
+    }
+
+    pub fn bugs(&self) -> u32 {
+        self.levels.values().map(|level| level.count_ones()).sum()
+    }
+
+    /// Bugs can spread one level further out and one level further in every minute
+    pub fn step(&self, rules: Rules) -> Self {
+        let masks = neighbour_masks();
+        let min = self.levels.keys().next().copied().unwrap_or(0) - 1;
+        let max = self.levels.keys().last().copied().unwrap_or(0) + 1;
+
+        let mut levels = BTreeMap::new();
+        for depth in min..=max {
+            let (outer, level, inner) = (
+                self.level(depth - 1).0,
+                self.level(depth).0,
+                self.level(depth + 1).0,
+            );
+            let mut next = 0;
+            for (tile, mask) in masks.iter().enumerate() {
+                if tile == CENTER {
+                    continue;
+                }
+                let neighbours = (level & mask.same).count_ones()
+                    + (outer & mask.outer).count_ones()
+                    + (inner & mask.inner).count_ones();
+                if rules.is_alive(level >> tile & 1 == 1, neighbours) {
+                    next |= 1 << tile;
+                }
+            }
+            if next != 0 {
+                levels.insert(depth, next);
+            }
+        }
+
+        RecursiveGrid { levels }
+    }
+
+    pub fn run(&self, rules: Rules, minutes: usize) -> Self {
+        (0..minutes).fold(self.clone(), |grid, _| grid.step(rules))
+    }
+}
+
+#[aoc_generator(day24)]
+fn generator_input(input: &str) -> Result<Grid, ParseGridError> {
+    Grid::parse(input)
+}
+
+#[aoc(day24, part1)]
+fn part1(grid: &Grid) -> u32 {
+    grid.first_repeated(Rules::DISCORD).biodiversity()
+}
+
+#[aoc(day24, part2)]
+fn part2(grid: &Grid) -> u32 {
+    RecursiveGrid::new(*grid)
+        .run(Rules::DISCORD, MINUTES)
+        .bugs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "....#
+                           #..#.
+                           #..##
+                           ..#..
+                           #....";
+
+    #[test]
+    fn test_day24_part1() {
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            grid.step(Rules::DISCORD).to_string(),
+            "#..#.\n####.\n###.#\n##.##\n.##..\n"
+        );
+        assert_eq!(part1(&grid), 2_129_920);
+    }
+
+    #[test]
+    fn test_day24_part2() {
+        let grid = RecursiveGrid::new(Grid::parse(EXAMPLE).unwrap()).run(Rules::DISCORD, 10);
+        assert_eq!(grid.bugs(), 99);
+        assert_eq!(
+            grid.level(-5).to_string(),
+            "..#..\n.#.#.\n....#\n.#.#.\n..#..\n"
+        );
+        assert_eq!(grid.level(6), Grid(0));
+    }
+
+    #[test]
+    fn test_rules() {
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.step(Rules::new(&[], &[]).unwrap()), Grid(0));
+        assert_eq!(Rules::new(&[1], &[1, 2]), Ok(Rules::DISCORD));
+        // every bug survives and nothing new appears
+        assert_eq!(grid.step(Rules::new(&[0, 1, 2, 3, 4], &[]).unwrap()), grid);
+
+        assert_eq!("B1,2/S1".parse(), Ok(Rules::DISCORD));
+        assert_eq!("B/S".parse(), Rules::new(&[], &[]));
+        assert_eq!(
+            "B16/S1".parse::<Rules>(),
+            Err(ParseRulesError::TooManyNeighbours(16))
+        );
+        assert!("B8/S0".parse::<Rules>().is_ok());
+        assert!("B1,x/S1".parse::<Rules>().is_err());
+        assert!("S1/B1".parse::<Rules>().is_err());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(Grid::parse("#...#\n....."), Err(ParseGridError));
+        assert_eq!(Grid::parse(&EXAMPLE.replace('#', "x")), Err(ParseGridError));
+    }
+}
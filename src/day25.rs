@@ -0,0 +1,648 @@
+use crate::intcode_computer::{parse_input, State, CPU, SIZE};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// Items that end the game or get the droid stuck
+const TRAPS: [&str; 5] = [
+    "escape pod",
+    "giant electromagnet",
+    "infinite loop",
+    "molten lava",
+    "photons",
+];
+
+/// Anything that answers commands like the droid does, so the explorer
+/// can be tested without the real adventure
+pub trait Terminal {
+    /// Everything printed before the first command
+    fn start(&mut self) -> String;
+    /// Everything printed after the command until the next prompt
+    fn command(&mut self, command: &str) -> String;
+    fn is_halted(&self) -> bool;
+}
+
+/// Ascii adapter for the Intcode adventure
+pub struct Droid {
+    cpu: CPU,
+    halted: bool,
+}
+
+impl Droid {
+    pub fn new(program: &[SIZE]) -> Self {
+        Droid {
+            cpu: CPU::new(program.to_owned()),
+            halted: false,
+        }
+    }
+
+    fn read(&mut self) -> String {
+        self.cpu.output.clear();
+        if let State::Halt = self.cpu.run() {
+            self.halted = true;
+        }
+        self.cpu.output_ascii()
+    }
+}
+
+impl Terminal for Droid {
+    fn start(&mut self) -> String {
+        self.read()
+    }
+
+    fn command(&mut self, command: &str) -> String {
+        self.cpu.input_ascii(&format!("{}\n", command));
+        self.read()
+    }
+
+    fn is_halted(&self) -> bool {
+        self.halted
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Room {
+    pub name: String,
+    pub description: String,
+    pub doors: Vec<String>,
+    pub items: Vec<String>,
+}
+
+/// Every room printed in the output, there are two of them when the droid
+/// gets ejected from the pressure-sensitive floor
+pub fn parse_rooms(output: &str) -> Vec<Room> {
+    let mut rooms: Vec<Room> = Vec::new();
+    let mut list = None;
+
+    for line in output.lines().map(str::trim) {
+        if line.starts_with("== ") && line.ends_with(" ==") {
+            rooms.push(Room {
+                name: line.trim_matches(|c| c == '=' || c == ' ').to_owned(),
+                ..Room::default()
+            });
+            list = None;
+            continue;
+        }
+        let room = match rooms.last_mut() {
+            Some(room) => room,
+            None => continue,
+        };
+
+        match line {
+            "Doors here lead:" => list = Some(true),
+            "Items here:" => list = Some(false),
+            "" => list = None,
+            _ => match (line.strip_prefix("- "), list) {
+                (Some(door), Some(true)) => room.doors.push(door.to_owned()),
+                (Some(item), Some(false)) => room.items.push(item.to_owned()),
+                _ if room.description.is_empty() && !line.starts_with("Command?") => {
+                    room.description = line.to_owned()
+                }
+                _ => (),
+            },
+        }
+    }
+
+    rooms
+}
+
+/// The number to type on the keypad once the droid got through
+pub fn parse_password(output: &str) -> Option<String> {
+    let rest = &output[output.find("typing ")? + "typing ".len()..];
+    let password: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    if password.is_empty() {
+        None
+    } else {
+        Some(password)
+    }
+}
+
+pub fn opposite(door: &str) -> &str {
+    match door {
+        "north" => "south",
+        "south" => "north",
+        "east" => "west",
+        "west" => "east",
+        _ => door,
+    }
+}
+
+/// Every room seen so far and where the doors lead
+#[derive(Debug, Default)]
+pub struct AutoMap {
+    pub rooms: BTreeMap<String, Room>,
+    pub doors: BTreeMap<(String, String), String>,
+}
+
+impl AutoMap {
+    pub fn record(&mut self, from: Option<&str>, door: &str, rooms: &[Room]) {
+        for room in rooms {
+            self.rooms.insert(room.name.clone(), room.clone());
+        }
+        if let (Some(from), Some(to)) = (from, rooms.first()) {
+            self.doors
+                .insert((from.to_owned(), door.to_owned()), to.name.clone());
+            self.doors.insert(
+                (to.name.clone(), opposite(door).to_owned()),
+                from.to_owned(),
+            );
+        }
+    }
+
+    /// Doors to go through, breadth first over the doors we already went through
+    pub fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut previous: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(from);
+        queue.push_back(from);
+
+        while let Some(room) = queue.pop_front() {
+            if room == to {
+                let mut path = Vec::new();
+                let mut room = to;
+                while let Some((before, door)) = previous.get(room) {
+                    path.push(door.to_string());
+                    room = before;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for ((start, door), next) in self.doors.iter() {
+                if start == room && seen.insert(next.as_str()) {
+                    previous.insert(next, (room, door));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl fmt::Display for AutoMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for room in self.rooms.values() {
+            writeln!(f, "{}", room.name)?;
+            for door in room.doors.iter() {
+                let to = self
+                    .doors
+                    .get(&(room.name.clone(), door.clone()))
+                    .map_or("?", String::as_str);
+                writeln!(f, "  {} -> {}", door, to)?;
+            }
+            if !room.items.is_empty() {
+                writeln!(f, "  items: {}", room.items.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdventureError {
+    /// The droid printed something that isn't a room
+    UnexpectedOutput(String),
+    NoCheckpoint,
+    /// Every combination of items was tried on the floor
+    NoPassword,
+}
+
+impl fmt::Display for AdventureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdventureError::UnexpectedOutput(output) => {
+                write!(f, "Expected a room, the droid said:\n{}", output)
+            }
+            AdventureError::NoCheckpoint => write!(f, "No pressure-sensitive floor was found"),
+            AdventureError::NoPassword => {
+                write!(f, "No combination of items gets through the floor")
+            }
+        }
+    }
+}
+
+impl Error for AdventureError {}
+
+enum Move {
+    Entered,
+    Ejected,
+    Finished(String),
+}
+
+/// Explores the whole ship picking everything up, then tries every set of items
+/// on the pressure-sensitive floor
+pub struct Explorer<T: Terminal> {
+    terminal: T,
+    pub map: AutoMap,
+    pub inventory: Vec<String>,
+    current: String,
+    /// Room before the pressure-sensitive floor and the door to it
+    checkpoint: Option<(String, String)>,
+}
+
+impl<T: Terminal> Explorer<T> {
+    pub fn new(mut terminal: T) -> Result<Self, AdventureError> {
+        let output = terminal.start();
+        let rooms = parse_rooms(&output);
+        let room = rooms
+            .last()
+            .ok_or_else(|| AdventureError::UnexpectedOutput(output.clone()))?;
+
+        let mut map = AutoMap::default();
+        let current = room.name.clone();
+        map.record(None, "", &rooms);
+        Ok(Explorer {
+            terminal,
+            map,
+            inventory: Vec::new(),
+            current,
+            checkpoint: None,
+        })
+    }
+
+    fn go(&mut self, door: &str) -> Result<Move, AdventureError> {
+        let output = self.terminal.command(door);
+        if let Some(password) = parse_password(&output) {
+            return Ok(Move::Finished(password));
+        }
+
+        let rooms = parse_rooms(&output);
+        let last = match rooms.last() {
+            Some(room) => room.name.clone(),
+            None => return Err(AdventureError::UnexpectedOutput(output)),
+        };
+        self.map.record(Some(&self.current), door, &rooms);
+
+        if rooms.len() > 1 && last == self.current {
+            self.checkpoint = Some((self.current.clone(), door.to_owned()));
+            return Ok(Move::Ejected);
+        }
+        self.current = last;
+        Ok(Move::Entered)
+    }
+
+    fn take(&mut self, item: &str) {
+        self.terminal.command(&format!("take {}", item));
+        self.inventory.push(item.to_owned());
+        if let Some(room) = self.map.rooms.get_mut(&self.current) {
+            room.items.retain(|other| other != item);
+        }
+    }
+
+    fn drop(&mut self, item: &str) {
+        self.terminal.command(&format!("drop {}", item));
+        self.inventory.retain(|other| other != item);
+    }
+
+    /// Depth first, coming back through the same door after every room.
+    /// Stops with the password if the droid already gets through the floor on the way
+    pub fn explore(&mut self) -> Result<Option<String>, AdventureError> {
+        let room = self.map.rooms[&self.current].clone();
+        for item in room.items.iter() {
+            if !TRAPS.contains(&item.as_str()) {
+                self.take(item);
+            }
+        }
+
+        for door in room.doors.iter() {
+            let key = (room.name.clone(), door.clone());
+            if self.map.doors.contains_key(&key) {
+                continue;
+            }
+            let known: HashSet<String> = self.map.rooms.keys().cloned().collect();
+            match self.go(door)? {
+                Move::Ejected => continue,
+                Move::Finished(password) => return Ok(Some(password)),
+                Move::Entered => {
+                    if !known.contains(&self.current) {
+                        if let Some(password) = self.explore()? {
+                            return Ok(Some(password));
+                        }
+                    }
+                    if let Move::Finished(password) = self.go(opposite(door))? {
+                        return Ok(Some(password));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Goes to the checkpoint and changes one item at a time, in Gray code order,
+    /// until the floor lets the droid through
+    pub fn crack_floor(&mut self) -> Result<String, AdventureError> {
+        let (checkpoint, door) = self
+            .checkpoint
+            .clone()
+            .ok_or(AdventureError::NoCheckpoint)?;
+        let path = self
+            .map
+            .path(&self.current, &checkpoint)
+            .ok_or(AdventureError::NoCheckpoint)?;
+        for step in path {
+            self.go(&step)?;
+        }
+
+        let items = self.inventory.clone();
+        for i in 0..(1_u64 << items.len()) {
+            if i > 0 {
+                let item = &items[i.trailing_zeros() as usize];
+                if self.inventory.contains(item) {
+                    self.drop(item);
+                } else {
+                    self.take(item);
+                }
+            }
+            if let Move::Finished(password) = self.go(&door)? {
+                return Ok(password);
+            }
+        }
+
+        Err(AdventureError::NoPassword)
+    }
+}
+
+/// Plays by hand, `map` shows every room seen so far
+pub fn play(
+    terminal: &mut impl Terminal,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut map = AutoMap::default();
+    let text = terminal.start();
+    let mut current = parse_rooms(&text).last().map(|room| room.name.clone());
+    map.record(None, "", &parse_rooms(&text));
+    write!(output, "{}", text)?;
+
+    for line in input.lines() {
+        let command = line?;
+        let command = command.trim();
+        if command == "map" {
+            writeln!(output, "{}", map)?;
+            continue;
+        }
+
+        let text = terminal.command(command);
+        if let (Some(item), Some(room)) = (
+            command.strip_prefix("take "),
+            current.as_ref().and_then(|name| map.rooms.get_mut(name)),
+        ) {
+            room.items.retain(|other| other != item);
+        }
+        let rooms = parse_rooms(&text);
+        map.record(current.as_deref(), command, &rooms);
+        if let Some(room) = rooms.last() {
+            current = Some(room.name.clone());
+        }
+        write!(output, "{}", text)?;
+
+        if terminal.is_halted() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[aoc_generator(day25)]
+fn generator_input(input: &str) -> Vec<SIZE> {
+    parse_input(input)
+}
+
+#[aoc(day25, part1)]
+fn part1(input: &[SIZE]) -> Result<String, AdventureError> {
+    let mut explorer = Explorer::new(Droid::new(input))?;
+    match explorer.explore()? {
+        Some(password) => Ok(password),
+        None => explorer.crack_floor(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKPOINT: &str = "Security Checkpoint";
+
+    struct FakeRoom {
+        name: &'static str,
+        doors: Vec<(&'static str, usize)>,
+        items: Vec<String>,
+    }
+
+    /// A tiny ship, the floor wants the mug and the wreath
+    struct FakeShip {
+        rooms: Vec<FakeRoom>,
+        current: usize,
+        inventory: Vec<String>,
+        commands: Vec<String>,
+        halted: bool,
+        /// Weight the floor lets through
+        weight: usize,
+    }
+
+    const FLOOR: usize = 5;
+
+    impl FakeShip {
+        fn new() -> Self {
+            let room = |name, doors, items: &[&str]| FakeRoom {
+                name,
+                doors,
+                items: items.iter().map(|item| item.to_string()).collect(),
+            };
+            FakeShip {
+                rooms: vec![
+                    room("Hull Breach", vec![("north", 1), ("east", 2)], &[]),
+                    room("Kitchen", vec![("south", 0)], &["mug"]),
+                    room(
+                        "Hallway",
+                        vec![("north", 3), ("east", 4), ("west", 0)],
+                        &["infinite loop", "coin"],
+                    ),
+                    room("Storage", vec![("south", 2)], &["wreath"]),
+                    room(CHECKPOINT, vec![("south", FLOOR), ("west", 2)], &[]),
+                    room("Pressure-Sensitive Floor", vec![("north", 4)], &[]),
+                ],
+                current: 0,
+                inventory: Vec::new(),
+                commands: Vec::new(),
+                halted: false,
+                weight: 5,
+            }
+        }
+
+        fn describe(&self, room: usize) -> String {
+            let room = &self.rooms[room];
+            let mut text = format!("\n\n\n== {} ==\nA room.\n\nDoors here lead:\n", room.name);
+            for (door, _) in room.doors.iter() {
+                text.push_str(&format!("- {}\n", door));
+            }
+            if !room.items.is_empty() {
+                text.push_str("\nItems here:\n");
+                for item in room.items.iter() {
+                    text.push_str(&format!("- {}\n", item));
+                }
+            }
+            text
+        }
+
+        fn weight(&self) -> usize {
+            self.inventory
+                .iter()
+                .map(|item| match item.as_str() {
+                    "mug" => 1,
+                    "coin" => 2,
+                    "wreath" => 4,
+                    _ => 100,
+                })
+                .sum()
+        }
+    }
+
+    impl Terminal for FakeShip {
+        fn start(&mut self) -> String {
+            self.describe(self.current) + "\nCommand?\n"
+        }
+
+        fn command(&mut self, command: &str) -> String {
+            self.commands.push(command.to_owned());
+            let current = self.current;
+
+            if let Some(item) = command.strip_prefix("take ") {
+                self.rooms[current].items.retain(|other| other != item);
+                self.inventory.push(item.to_owned());
+                return format!("\nYou take the {}.\n\nCommand?\n", item);
+            }
+            if let Some(item) = command.strip_prefix("drop ") {
+                self.inventory.retain(|other| other != item);
+                self.rooms[current].items.push(item.to_owned());
+                return format!("\nYou drop the {}.\n\nCommand?\n", item);
+            }
+
+            let next = match self.rooms[current]
+                .doors
+                .iter()
+                .find(|(d, _)| *d == command)
+            {
+                Some((_, next)) => *next,
+                None => return String::from("\nYou can't go that way.\n\nCommand?\n"),
+            };
+            if next != FLOOR {
+                self.current = next;
+                return self.describe(next) + "\nCommand?\n";
+            }
+
+            let floor = self.describe(FLOOR);
+            match self.weight() {
+                weight if weight == self.weight => {
+                    self.halted = true;
+                    floor
+                        + "A loud, robotic voice says \"Analysis complete! You may proceed.\" \
+                           \"Oh, hello! You should be able to get in by typing 12345 on the \
+                           keypad at the main airlock.\"\n"
+                }
+                weight => {
+                    let comparison = if weight > self.weight {
+                        "heavier"
+                    } else {
+                        "lighter"
+                    };
+                    format!(
+                        "{}A loud, robotic voice says \"Alert! Droids on this ship are {} than \
+                         the detected value!\" and you are ejected back to the checkpoint.\n{}\nCommand?\n",
+                        floor,
+                        comparison,
+                        self.describe(current)
+                    )
+                }
+            }
+        }
+
+        fn is_halted(&self) -> bool {
+            self.halted
+        }
+    }
+
+    #[test]
+    fn test_parse_rooms() {
+        let ship = FakeShip::new();
+        let rooms = parse_rooms(&ship.describe(2));
+        assert_eq!(
+            rooms,
+            vec![Room {
+                name: String::from("Hallway"),
+                description: String::from("A room."),
+                doors: vec![
+                    String::from("north"),
+                    String::from("east"),
+                    String::from("west")
+                ],
+                items: vec![String::from("infinite loop"), String::from("coin")],
+            }]
+        );
+        assert_eq!(
+            parse_password("by typing 2424308736 on the keypad"),
+            Some(String::from("2424308736"))
+        );
+    }
+
+    #[test]
+    fn test_explore_fake_ship() {
+        let mut explorer = Explorer::new(FakeShip::new()).unwrap();
+        assert_eq!(explorer.explore(), Ok(None));
+
+        let mut inventory = explorer.inventory.clone();
+        inventory.sort();
+        assert_eq!(inventory, vec!["coin", "mug", "wreath"]);
+        assert_eq!(explorer.map.rooms.len(), 6);
+        assert_eq!(
+            explorer.map.path("Hull Breach", CHECKPOINT),
+            Some(vec![String::from("east"), String::from("east")])
+        );
+
+        let explored = explorer.terminal.commands.len();
+        assert_eq!(explorer.crack_floor(), Ok(String::from("12345")));
+        let commands = &explorer.terminal.commands;
+        assert!(!commands.contains(&String::from("take infinite loop")));
+
+        // one item changes between every try
+        let tries = commands[explored..]
+            .iter()
+            .filter(|c| *c == "south")
+            .count();
+        let changes = commands[explored..]
+            .iter()
+            .filter(|c| c.starts_with("take ") || c.starts_with("drop "))
+            .count();
+        assert_eq!(changes, tries - 1);
+    }
+
+    #[test]
+    fn test_explore_through_floor() {
+        // everything on the ship is exactly the right weight
+        let mut ship = FakeShip::new();
+        ship.weight = 7;
+        let mut explorer = Explorer::new(ship).unwrap();
+        assert_eq!(explorer.explore(), Ok(Some(String::from("12345"))));
+
+        let commands = &explorer.terminal.commands;
+        assert_eq!(commands.last().map(String::as_str), Some("south"));
+        assert!(explorer.terminal.is_halted());
+    }
+
+    #[test]
+    fn test_play_auto_map() {
+        let mut ship = FakeShip::new();
+        let mut output = Vec::new();
+        let commands = "north\ntake mug\nsouth\nmap\n";
+        play(&mut ship, commands.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Hull Breach\n  north -> Kitchen\n  east -> ?\n"));
+        assert!(output.contains("Kitchen\n  south -> Hull Breach\n"));
+        assert!(!output.contains("items: mug"));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::intcode_computer::{State, CPU, SIZE};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+#[derive(Default)]
+struct ChannelInner {
+    values: VecDeque<SIZE>,
+    waker: Option<Waker>,
+}
+
+/// Queue of values shared between machines and controllers.
+/// Receiving from an empty channel suspends until a value is sent.
+#[derive(Clone, Default)]
+pub struct Channel {
+    inner: Rc<RefCell<ChannelInner>>,
+}
+
+impl Channel {
+    pub fn new() -> Self {
+        Channel::default()
+    }
+
+    pub fn send(&self, value: SIZE) {
+        let mut inner = self.inner.borrow_mut();
+        inner.values.push_back(value);
+        if let Some(waker) = inner.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<SIZE> {
+        self.inner.borrow_mut().values.pop_front()
+    }
+
+    pub fn recv(&self) -> Recv {
+        Recv {
+            channel: self.clone(),
+        }
+    }
+}
+
+pub struct Recv {
+    channel: Channel,
+}
+
+impl Future for Recv {
+    type Output = SIZE;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<SIZE> {
+        let mut inner = self.channel.inner.borrow_mut();
+        match inner.values.pop_front() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                inner.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Async wrapper around a `CPU`, input instructions wait on the input channel
+pub struct Machine {
+    cpu: CPU,
+    input: Channel,
+}
+
+impl Machine {
+    pub fn new(memory: Vec<SIZE>, input: Channel) -> Self {
+        let mut cpu = CPU::new(memory);
+        cpu.halt_on_output = true;
+        Machine { cpu, input }
+    }
+
+    pub fn send(&self, value: SIZE) {
+        self.input.send(value);
+    }
+
+    /// Runs until the next output, returns None once the program halts
+    pub async fn output(&mut self) -> Option<SIZE> {
+        loop {
+            match self.cpu.run() {
+                State::Output(value) => return Some(value),
+                State::Input => {
+                    let value = self.input.recv().await;
+                    self.cpu.input.push_back(value);
+                }
+                State::Halt => return None,
+                _ => (),
+            }
+        }
+    }
+
+    /// Sends every output to the channel until the program halts,
+    /// yielding after each one so the receiving side can run.
+    /// Returns the last value that was sent.
+    pub async fn pipe(mut self, output: Channel) -> Option<SIZE> {
+        let mut last = None;
+        while let Some(value) = self.output().await {
+            output.send(value);
+            last = Some(value);
+            yield_now().await;
+        }
+        last
+    }
+}
+
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Gives control back to the executor once
+pub fn yield_now() -> impl Future<Output = ()> {
+    YieldNow { yielded: false }
+}
+
+pub struct JoinAll<F: Future> {
+    futures: Vec<Pin<Box<F>>>,
+    outputs: Vec<Option<F::Output>>,
+}
+
+// The futures are boxed so they never move, and the outputs are never pinned
+impl<F: Future> Unpin for JoinAll<F> {}
+
+impl<F: Future> Future for JoinAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+        for (future, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+            if output.is_none() {
+                if let Poll::Ready(value) = future.as_mut().poll(cx) {
+                    *output = Some(value);
+                }
+            }
+        }
+
+        if this.outputs.iter().all(Option::is_some) {
+            Poll::Ready(this.outputs.iter_mut().map(|x| x.take().unwrap()).collect())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Runs every future concurrently, the outputs are in the same order as the futures
+pub fn join_all<F: Future>(futures: Vec<F>) -> JoinAll<F> {
+    let outputs = futures.iter().map(|_| None).collect();
+    JoinAll {
+        futures: futures.into_iter().map(Box::pin).collect(),
+        outputs,
+    }
+}
+
+struct WakeFlag(AtomicBool);
+
+impl Wake for WakeFlag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Minimal single threaded executor.
+/// Nothing else can wake the future, so if it's pending without being woken
+/// every machine is waiting on input that will never come and it panics instead of hanging.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let flag = Arc::new(WakeFlag(AtomicBool::new(false)));
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        if !flag.0.swap(false, Ordering::SeqCst) {
+            panic!("Deadlock: every machine is waiting for input");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // echoes every input until it reads 0
+    const ECHO: [SIZE; 11] = [3, 20, 4, 20, 1005, 20, 0, 99, 0, 0, 0];
+
+    #[test]
+    fn test_output_await() {
+        let mut machine = Machine::new(ECHO.to_vec(), Channel::new());
+
+        let outputs = block_on(async {
+            let mut outputs = vec![];
+            for value in 1..=3 {
+                machine.send(value);
+                outputs.push(machine.output().await);
+            }
+            machine.send(0);
+            outputs.push(machine.output().await);
+            outputs.push(machine.output().await);
+            outputs
+        });
+
+        assert_eq!(outputs, vec![Some(1), Some(2), Some(3), Some(0), None]);
+    }
+
+    #[test]
+    fn test_pipe_chain() {
+        let first = Channel::new();
+        let middle = Channel::new();
+        let last = Channel::new();
+        for value in [4, 2, 0].iter() {
+            first.send(*value);
+        }
+
+        let machines = vec![
+            Machine::new(ECHO.to_vec(), first).pipe(middle.clone()),
+            Machine::new(ECHO.to_vec(), middle).pipe(last.clone()),
+        ];
+
+        assert_eq!(block_on(join_all(machines)), vec![Some(0), Some(0)]);
+        assert_eq!(last.try_recv(), Some(4));
+        assert_eq!(last.try_recv(), Some(2));
+        assert_eq!(last.try_recv(), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Deadlock")]
+    fn test_deadlock() {
+        let mut machine = Machine::new(ECHO.to_vec(), Channel::new());
+        block_on(machine.output());
+    }
+}
@@ -1,16 +1,21 @@
+use crate::intcode_loader;
+use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::ops::Range;
+use std::rc::Rc;
 
+#[allow(clippy::upper_case_acronyms)]
 pub type SIZE = i64;
 
-#[derive(PartialEq, Debug)]
-enum ParamMode {
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum ParamMode {
     Immediate,
     Position,
     Relative,
 }
 
 impl ParamMode {
-    fn from(param: SIZE) -> Self {
+    pub(crate) fn from(param: SIZE) -> Self {
         match param {
             0 => ParamMode::Position,
             1 => ParamMode::Immediate,
@@ -20,7 +25,8 @@ impl ParamMode {
     }
 }
 
-enum Opcode {
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum Opcode {
     Add,
     Multiply,
     Input,
@@ -34,7 +40,7 @@ enum Opcode {
 }
 
 impl Opcode {
-    fn from(opcode: SIZE) -> Self {
+    pub(crate) fn from(opcode: SIZE) -> Self {
         match opcode {
             1 => Opcode::Add,
             2 => Opcode::Multiply,
@@ -49,9 +55,18 @@ impl Opcode {
             _ => panic!("Unknown opcode"),
         }
     }
+
+    pub(crate) fn param_count(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Multiply | Opcode::JmpLessThan | Opcode::JmpEquals => 3,
+            Opcode::JmpTrue | Opcode::JmpFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::SetRelativeBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
 }
 
-fn parse_instruction(instruction: SIZE) -> (SIZE, ParamMode, ParamMode, ParamMode) {
+pub(crate) fn parse_instruction(instruction: SIZE) -> (SIZE, ParamMode, ParamMode, ParamMode) {
     let opcode = instruction % 100;
     let a = ParamMode::from((instruction / 100) % 10);
     let b = ParamMode::from((instruction / 1000) % 10);
@@ -59,6 +74,12 @@ fn parse_instruction(instruction: SIZE) -> (SIZE, ParamMode, ParamMode, ParamMod
     (opcode, a, b, c)
 }
 
+/// Whether the instruction can be executed, unknown opcodes and modes panic when they are
+pub(crate) fn is_valid_instruction(instruction: SIZE) -> bool {
+    matches!(instruction % 100, 1..=9 | 99)
+        && (2..5).all(|digit| (0..=2).contains(&((instruction / 10_i64.pow(digit)) % 10)))
+}
+
 #[derive(PartialEq)]
 pub enum State {
     Running,
@@ -68,6 +89,107 @@ pub enum State {
     OutputVec(Vec<SIZE>),
 }
 
+/// Hardware that can be attached to a range of addresses of the `CPU` memory.
+/// Reads and writes in that range are forwarded to the device instead of `memory`.
+/// The offset is relative to the start of the mapped region.
+pub trait Device {
+    fn read(&mut self, offset: SIZE) -> SIZE;
+    fn write(&mut self, offset: SIZE, value: SIZE);
+    /// What a read would return, without any of its side effects
+    fn peek(&self, offset: SIZE) -> SIZE;
+}
+
+/// Allows keeping a handle on a device after it's mapped, to inspect it once the program ran
+impl<D: Device> Device for Rc<RefCell<D>> {
+    fn read(&mut self, offset: SIZE) -> SIZE {
+        self.borrow_mut().read(offset)
+    }
+
+    fn write(&mut self, offset: SIZE, value: SIZE) {
+        self.borrow_mut().write(offset, value)
+    }
+
+    fn peek(&self, offset: SIZE) -> SIZE {
+        self.borrow().peek(offset)
+    }
+}
+
+/// Returns an incrementing value every time it's read, writing to it resets the count
+#[derive(Default)]
+pub struct Counter {
+    pub count: SIZE,
+}
+
+impl Device for Counter {
+    fn read(&mut self, _offset: SIZE) -> SIZE {
+        let count = self.count;
+        self.count += 1;
+        count
+    }
+
+    fn write(&mut self, _offset: SIZE, value: SIZE) {
+        self.count = value;
+    }
+
+    fn peek(&self, _offset: SIZE) -> SIZE {
+        self.count
+    }
+}
+
+/// A width * height grid of tiles stored row by row
+pub struct Framebuffer {
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Vec<SIZE>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Framebuffer {
+            width,
+            height,
+            tiles: vec![0; width * height],
+        }
+    }
+
+    pub fn len(&self) -> SIZE {
+        self.tiles.len() as SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    pub fn render(&self, tile_to_char: impl Fn(SIZE) -> char) -> String {
+        let mut screen = String::new();
+        for row in self.tiles.chunks(self.width) {
+            screen.extend(row.iter().map(|tile| tile_to_char(*tile)));
+            screen.push('\n');
+        }
+        screen
+    }
+}
+
+impl Device for Framebuffer {
+    fn read(&mut self, offset: SIZE) -> SIZE {
+        self.tiles[offset as usize]
+    }
+
+    fn write(&mut self, offset: SIZE, value: SIZE) {
+        self.tiles[offset as usize] = value;
+    }
+
+    fn peek(&self, offset: SIZE) -> SIZE {
+        self.tiles[offset as usize]
+    }
+}
+
+struct MappedDevice {
+    region: Range<SIZE>,
+    device: Box<dyn Device>,
+}
+
+#[allow(clippy::upper_case_acronyms)]
 pub struct CPU {
     pub input: VecDeque<SIZE>,
     pub output: Vec<SIZE>,
@@ -77,6 +199,7 @@ pub struct CPU {
     pub memory: Vec<SIZE>,
     relative_base: SIZE,
     pub allow_print: bool,
+    devices: Vec<MappedDevice>,
 }
 
 impl CPU {
@@ -90,7 +213,33 @@ impl CPU {
             relative_base: 0,
             allow_print: cfg!(test),
             halt_on_output_size: 1,
+            devices: Vec::new(),
+        }
+    }
+
+    /// Panics if the region overlaps a region that is already mapped
+    pub fn map_device(&mut self, region: Range<SIZE>, device: impl Device + 'static) {
+        if let Some(mapped) = self
+            .devices
+            .iter()
+            .find(|mapped| mapped.region.start < region.end && region.start < mapped.region.end)
+        {
+            panic!(
+                "Device region {:?} overlaps already mapped region {:?}",
+                region, mapped.region
+            );
         }
+
+        self.devices.push(MappedDevice {
+            region,
+            device: Box::new(device),
+        });
+    }
+
+    fn find_device(&mut self, addr: SIZE) -> Option<&mut MappedDevice> {
+        self.devices
+            .iter_mut()
+            .find(|mapped| mapped.region.contains(&addr))
     }
 
     fn fetch(&mut self) -> SIZE {
@@ -99,16 +248,56 @@ impl CPU {
         instruction
     }
 
-    fn get(&mut self, addr: SIZE) -> SIZE {
+    pub fn instruction_pointer(&self) -> SIZE {
+        self.instruction_pointer
+    }
+
+    pub fn relative_base(&self) -> SIZE {
+        self.relative_base
+    }
+
+    /// Reads memory the same way an instruction would, mapped devices included
+    pub fn get(&mut self, addr: SIZE) -> SIZE {
+        if let Some(mapped) = self.find_device(addr) {
+            return mapped.device.read(addr - mapped.region.start);
+        }
         self.memory_size_check(addr);
         self.memory[addr as usize]
     }
 
-    fn set(&mut self, addr: SIZE, val: SIZE) {
+    /// Writes memory the same way an instruction would, mapped devices included
+    pub fn set(&mut self, addr: SIZE, val: SIZE) {
+        if let Some(mapped) = self.find_device(addr) {
+            return mapped.device.write(addr - mapped.region.start, val);
+        }
         self.memory_size_check(addr);
         self.memory[addr as usize] = val;
     }
 
+    /// Reads memory without side effects, devices are peeked and memory past the end is 0
+    pub fn peek(&self, addr: SIZE) -> SIZE {
+        if let Some(mapped) = self
+            .devices
+            .iter()
+            .find(|mapped| mapped.region.contains(&addr))
+        {
+            return mapped.device.peek(addr - mapped.region.start);
+        }
+        if addr < 0 {
+            return 0;
+        }
+        self.memory.get(addr as usize).copied().unwrap_or(0)
+    }
+
+    /// True when reading or writing there wouldn't need to grow memory
+    pub fn is_allocated(&self, addr: SIZE) -> bool {
+        (0..self.memory.len() as SIZE).contains(&addr)
+            || self
+                .devices
+                .iter()
+                .any(|mapped| mapped.region.contains(&addr))
+    }
+
     fn memory_size_check(&mut self, addr: SIZE) {
         if self.memory.get(addr as usize).is_none() {
             self.memory.resize(addr as usize + 1, 0)
@@ -222,6 +411,20 @@ impl CPU {
         self.run_with_input(None)
     }
 
+    /// Queues every character of the text as input
+    pub fn input_ascii(&mut self, text: &str) {
+        self.input.extend(text.bytes().map(SIZE::from));
+    }
+
+    /// Output printed as text, values that aren't ascii are skipped
+    pub fn output_ascii(&self) -> String {
+        self.output
+            .iter()
+            .filter(|value| (0..128).contains(*value))
+            .map(|value| *value as u8 as char)
+            .collect()
+    }
+
     pub fn run_with_input(&mut self, input: Option<SIZE>) -> State {
         if let Some(value) = input {
             self.input.push_back(value);
@@ -237,11 +440,12 @@ impl CPU {
     }
 }
 
+/// See `intcode_loader` for the accepted formats, this panics on invalid programs
 pub fn parse_input(input: &str) -> Vec<SIZE> {
-    input
-        .split(',')
-        .map(|x| x.parse().expect("Not a number"))
-        .collect()
+    match intcode_loader::parse_text(input) {
+        Ok(program) => program,
+        Err(err) => panic!("{}", err),
+    }
 }
 
 pub fn parse_code(input: &[SIZE]) -> Vec<SIZE> {
@@ -278,6 +482,12 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_input_whitespace() {
+        assert_eq!(parse_input("1,0,0,3,99\n"), vec![1, 0, 0, 3, 99]);
+        assert_eq!(parse_input(" 1, 0,0 ,3,99 \r\n"), vec![1, 0, 0, 3, 99]);
+    }
+
     #[test]
     fn test_parse_instruction() {
         let (opcode, p1, p2, p3) = parse_instruction(1002);
@@ -395,4 +605,40 @@ mod tests {
         println!("{:?}", cpu.output);
         assert_eq!(cpu.output[0], 1_125_899_906_842_624);
     }
+
+    #[test]
+    fn test_counter_device() {
+        // output the value at 100 three times
+        let code = vec![4, 100, 4, 100, 4, 100, 99];
+        let mut cpu = CPU::new(code);
+        cpu.map_device(100..101, Counter { count: 5 });
+        cpu.run();
+        assert_eq!(cpu.output, vec![5, 6, 7]);
+        assert!(cpu.memory.len() < 100, "device reads don't touch memory");
+    }
+
+    #[test]
+    fn test_framebuffer_device() {
+        // write 1 at (1, 0) and 2 at (0, 1) then read back (1, 0)
+        let code = vec![1101, 0, 1, 51, 1101, 0, 2, 52, 4, 51, 99];
+        let framebuffer = Rc::new(RefCell::new(Framebuffer::new(2, 2)));
+        let mut cpu = CPU::new(code);
+        cpu.map_device(50..54, framebuffer.clone());
+        cpu.run();
+
+        assert_eq!(cpu.output, vec![1]);
+        let screen = framebuffer.borrow().render(|tile| match tile {
+            0 => '.',
+            _ => '#',
+        });
+        assert_eq!(screen, ".#\n#.\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overlapping_devices() {
+        let mut cpu = CPU::new(vec![99]);
+        cpu.map_device(10..20, Counter::default());
+        cpu.map_device(15..25, Counter::default());
+    }
 }
@@ -0,0 +1,311 @@
+use crate::intcode_computer::{is_valid_instruction, State, CPU, SIZE};
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+
+/// Line based debug protocol, every command gets a single line response.
+///
+/// ```text
+/// break <addr>          ok
+/// clear <addr>          ok
+/// step                  running | input | output <value> | halted
+/// continue              break <addr> | input | output <value> | halted
+/// read <addr> [count]   <value> <value> ...      (0 past the end of memory, count <= 1024)
+/// write <addr> <value>  ok
+/// input <value>         ok
+/// regs                  ip=<ip> rb=<rb> input=<pending inputs> output=<every output so far>
+/// quit                  bye
+/// ```
+///
+/// Errors are reported as `error <message>`. Writing past the end of memory is an
+/// error, so a client can't make the session allocate memory the program never uses.
+/// Reads never trigger mapped devices, and stepping onto an invalid instruction is an error.
+pub struct Debugger {
+    pub cpu: CPU,
+    /// Every value the program output, the cpu halts on each one so `step` can report it
+    pub outputs: Vec<SIZE>,
+    breakpoints: BTreeSet<SIZE>,
+    halted: bool,
+}
+
+const MAX_READ: SIZE = 1024;
+
+fn parse_arg(arg: Option<&str>, name: &str) -> Result<SIZE, String> {
+    let arg = arg.ok_or(format!("missing {}", name))?;
+    arg.parse()
+        .map_err(|_| format!("{} is not a number: {}", name, arg))
+}
+
+fn parse_addr(arg: Option<&str>) -> Result<SIZE, String> {
+    let addr = parse_arg(arg, "addr")?;
+    if addr < 0 {
+        return Err(format!("negative addr: {}", addr));
+    }
+    Ok(addr)
+}
+
+impl Debugger {
+    pub fn new(mut cpu: CPU) -> Self {
+        cpu.halt_on_output = true;
+        cpu.halt_on_output_size = 1;
+        Debugger {
+            cpu,
+            outputs: Vec::new(),
+            breakpoints: BTreeSet::new(),
+            halted: false,
+        }
+    }
+
+    fn step(&mut self) -> Result<String, String> {
+        if self.halted {
+            return Ok(String::from("halted"));
+        }
+        // the cpu panics on those, that would take the whole session down
+        let ip = self.cpu.instruction_pointer();
+        let instruction = self.cpu.peek(ip);
+        if !is_valid_instruction(instruction) {
+            return Err(format!("invalid opcode {} at {}", instruction, ip));
+        }
+
+        Ok(match self.cpu.step() {
+            State::Running => String::from("running"),
+            State::Input => String::from("input"),
+            State::Output(value) => {
+                self.outputs.push(value);
+                format!("output {}", value)
+            }
+            State::OutputVec(values) => {
+                self.outputs.extend(&values);
+                format!(
+                    "output {}",
+                    values
+                        .iter()
+                        .map(|value| value.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )
+            }
+            State::Halt => {
+                self.halted = true;
+                String::from("halted")
+            }
+        })
+    }
+
+    fn resume(&mut self) -> Result<String, String> {
+        loop {
+            let response = self.step()?;
+            if response != "running" {
+                return Ok(response);
+            }
+            let ip = self.cpu.instruction_pointer();
+            if self.breakpoints.contains(&ip) {
+                return Ok(format!("break {}", ip));
+            }
+        }
+    }
+
+    /// Executes a single command of the protocol
+    pub fn execute(&mut self, command: &str) -> Result<String, String> {
+        let mut args = command.split_whitespace();
+        let ok = String::from("ok");
+
+        match args.next() {
+            Some("break") => {
+                self.breakpoints.insert(parse_addr(args.next())?);
+                Ok(ok)
+            }
+            Some("clear") => {
+                self.breakpoints.remove(&parse_addr(args.next())?);
+                Ok(ok)
+            }
+            Some("step") => self.step(),
+            Some("continue") => self.resume(),
+            Some("read") => {
+                let addr = parse_addr(args.next())?;
+                let count = match args.next() {
+                    Some(count) => parse_arg(Some(count), "count")?,
+                    None => 1,
+                };
+                if !(0..=MAX_READ).contains(&count) {
+                    return Err(format!("count must be between 0 and {}", MAX_READ));
+                }
+                let end = addr
+                    .checked_add(count)
+                    .ok_or_else(|| format!("addr {} + count {} overflows", addr, count))?;
+                Ok((addr..end)
+                    .map(|addr| self.cpu.peek(addr).to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "))
+            }
+            Some("write") => {
+                let addr = parse_addr(args.next())?;
+                let value = parse_arg(args.next(), "value")?;
+                if !self.cpu.is_allocated(addr) {
+                    return Err(format!("addr {} is past the end of memory", addr));
+                }
+                self.cpu.set(addr, value);
+                Ok(ok)
+            }
+            Some("input") => {
+                self.cpu.input.push_back(parse_arg(args.next(), "value")?);
+                Ok(ok)
+            }
+            Some("regs") => Ok(format!(
+                "ip={} rb={} input={:?} output={:?}",
+                self.cpu.instruction_pointer(),
+                self.cpu.relative_base(),
+                self.cpu.input,
+                self.outputs
+            )),
+            Some("quit") => Ok(String::from("bye")),
+            Some(unknown) => Err(format!("unknown command {}", unknown)),
+            None => Err(String::from("empty command")),
+        }
+    }
+
+    /// Answers commands from the stream until it's closed or the client sends quit
+    pub fn serve<S: Read + Write>(&mut self, stream: S) -> io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+
+        while reader.read_line(&mut line)? > 0 {
+            let response = match self.execute(line.trim()) {
+                Ok(response) => response,
+                Err(message) => format!("error {}", message),
+            };
+            writeln!(reader.get_mut(), "{}", response)?;
+            reader.get_mut().flush()?;
+
+            if response == "bye" {
+                break;
+            }
+            line.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Waits for a single client on a tcp port, use a localhost address to keep it local
+    pub fn serve_tcp(&mut self, addr: impl ToSocketAddrs) -> io::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        let (stream, _) = listener.accept()?;
+        self.serve(stream)
+    }
+
+    /// Waits for a single client on a unix socket
+    #[cfg(unix)]
+    pub fn serve_unix(&mut self, path: impl AsRef<std::path::Path>) -> io::Result<()> {
+        let listener = std::os::unix::net::UnixListener::bind(path)?;
+        let (stream, _) = listener.accept()?;
+        self.serve(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode_computer::Counter;
+    use std::cell::RefCell;
+    use std::net::TcpStream;
+    use std::rc::Rc;
+    use std::thread;
+
+    // adds the input to itself and outputs it
+    fn debugger() -> Debugger {
+        Debugger::new(CPU::new(vec![3, 9, 1, 9, 9, 9, 4, 9, 99, 0]))
+    }
+
+    #[test]
+    fn test_execute() {
+        let mut debugger = debugger();
+
+        assert_eq!(debugger.execute("step"), Ok(String::from("input")));
+        assert_eq!(debugger.execute("input 21"), Ok(String::from("ok")));
+        assert_eq!(debugger.execute("break 6"), Ok(String::from("ok")));
+        assert_eq!(debugger.execute("continue"), Ok(String::from("break 6")));
+        assert_eq!(debugger.execute("read 9"), Ok(String::from("42")));
+        assert_eq!(debugger.execute("write 9 7"), Ok(String::from("ok")));
+        assert_eq!(
+            debugger.execute("regs").unwrap(),
+            "ip=6 rb=0 input=[] output=[]"
+        );
+        assert_eq!(debugger.execute("step"), Ok(String::from("output 7")));
+        assert_eq!(debugger.execute("continue"), Ok(String::from("halted")));
+        assert_eq!(debugger.outputs, vec![7]);
+        assert_eq!(debugger.execute("read 6 3"), Ok(String::from("4 9 99")));
+        assert_eq!(debugger.execute("read 8 4"), Ok(String::from("99 7 0 0")));
+        assert_eq!(debugger.execute("read 100000000000"), Ok(String::from("0")));
+        assert_eq!(debugger.cpu.memory.len(), 10);
+    }
+
+    #[test]
+    fn test_execute_errors() {
+        let mut debugger = debugger();
+
+        assert!(debugger.execute("").is_err());
+        assert!(debugger.execute("jump 4").is_err());
+        assert!(debugger.execute("break").is_err());
+        assert!(debugger.execute("read -1").is_err());
+        assert!(debugger.execute("write 4 x").is_err());
+        assert!(debugger.execute("write 10 1").is_err());
+        assert!(debugger.execute("read 9223372036854775806 5").is_err());
+        assert!(debugger.execute("read 0 1025").is_err());
+        assert!(debugger.execute("read 0 -1").is_err());
+        assert_eq!(debugger.cpu.memory.len(), 10);
+
+        assert_eq!(debugger.execute("write 0 42"), Ok(String::from("ok")));
+        assert_eq!(
+            debugger.execute("step"),
+            Err(String::from("invalid opcode 42 at 0"))
+        );
+        assert_eq!(debugger.execute("write 0 503"), Ok(String::from("ok")));
+        assert_eq!(
+            debugger.execute("continue"),
+            Err(String::from("invalid opcode 503 at 0"))
+        );
+    }
+
+    #[test]
+    fn test_read_device() {
+        let counter = Rc::new(RefCell::new(Counter { count: 5 }));
+        let mut cpu = CPU::new(vec![99]);
+        cpu.map_device(100..102, Rc::clone(&counter));
+        let mut debugger = Debugger::new(cpu);
+
+        assert_eq!(debugger.execute("read 100 2"), Ok(String::from("5 5")));
+        assert_eq!(debugger.execute("read 100"), Ok(String::from("5")));
+        assert_eq!(counter.borrow().count, 5);
+    }
+
+    #[test]
+    fn test_serve_tcp() -> io::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+
+        let client = thread::spawn(move || -> io::Result<Vec<String>> {
+            let mut stream = TcpStream::connect(addr)?;
+            stream.write_all(b"input 2\nbreak 6\ncontinue\nread 9\nfoo\ncontinue\nquit\n")?;
+            BufReader::new(stream).lines().collect()
+        });
+
+        let (stream, _) = listener.accept()?;
+        let mut debugger = debugger();
+        debugger.serve(stream)?;
+
+        let responses = client.join().unwrap()?;
+        assert_eq!(
+            responses,
+            vec![
+                "ok",
+                "ok",
+                "break 6",
+                "4",
+                "error unknown command foo",
+                "output 4",
+                "bye"
+            ]
+        );
+        Ok(())
+    }
+}
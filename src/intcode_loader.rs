@@ -0,0 +1,229 @@
+use crate::intcode_computer::SIZE;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// Header of the binary format, followed by every word as a zigzag encoded LEB128 varint
+const BINARY_MAGIC: &[u8] = b"ICv1";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    InvalidNumber { line: usize, token: String },
+    EmptyValue { line: usize },
+    InvalidUtf8,
+    TruncatedVarint,
+    VarintOverflow,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "Failed to read program: {}", err),
+            LoadError::InvalidNumber { line, token } => {
+                write!(f, "Not a number on line {}: {:?}", line, token)
+            }
+            LoadError::EmptyValue { line } => write!(f, "Empty value on line {}", line),
+            LoadError::InvalidUtf8 => write!(f, "Text program is not valid utf-8"),
+            LoadError::TruncatedVarint => write!(f, "Binary program ends in the middle of a word"),
+            LoadError::VarintOverflow => write!(f, "Binary program has a word that is too large"),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
+
+/// Parses decimal words separated by commas, whitespace or newlines.
+/// Everything after a `#` or `;` is a comment until the end of the line.
+/// A trailing comma at the end of a line is allowed.
+pub fn parse_text(input: &str) -> Result<Vec<SIZE>, LoadError> {
+    let mut program = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line_number = i + 1;
+        let code = line.split(&['#', ';'][..]).next().unwrap_or("");
+        let values: Vec<&str> = code.split(',').collect();
+
+        for (j, value) in values.iter().enumerate() {
+            let mut tokens = value.split_whitespace().peekable();
+            if tokens.peek().is_none() {
+                if j == values.len() - 1 {
+                    continue;
+                }
+                return Err(LoadError::EmptyValue { line: line_number });
+            }
+            for token in tokens {
+                program.push(token.parse().map_err(|_| LoadError::InvalidNumber {
+                    line: line_number,
+                    token: token.to_owned(),
+                })?);
+            }
+        }
+    }
+
+    Ok(program)
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<Vec<SIZE>, LoadError> {
+    let mut program = Vec::new();
+    let mut value: u64 = 0;
+    let mut shift = 0;
+
+    for byte in bytes {
+        // the tenth byte only has room for the last bit
+        if shift >= 64 || (shift == 63 && byte & 0x7e != 0) {
+            return Err(LoadError::VarintOverflow);
+        }
+        value |= u64::from(byte & 0x7f) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            // zigzag decoding
+            program.push((value >> 1) as SIZE ^ -((value & 1) as SIZE));
+            value = 0;
+            shift = 0;
+        }
+    }
+
+    if shift != 0 {
+        return Err(LoadError::TruncatedVarint);
+    }
+
+    Ok(program)
+}
+
+/// Detects the format from the first bytes, gzip files can contain either of the other formats
+pub fn load(bytes: &[u8]) -> Result<Vec<SIZE>, LoadError> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+        load(&decompressed)
+    } else if bytes.starts_with(BINARY_MAGIC) {
+        parse_binary(&bytes[BINARY_MAGIC.len()..])
+    } else {
+        parse_text(std::str::from_utf8(bytes).map_err(|_| LoadError::InvalidUtf8)?)
+    }
+}
+
+pub fn load_file(path: impl AsRef<Path>) -> Result<Vec<SIZE>, LoadError> {
+    load(&fs::read(path)?)
+}
+
+pub fn write_binary(program: &[SIZE], mut writer: impl Write) -> io::Result<()> {
+    let mut bytes = BINARY_MAGIC.to_vec();
+
+    for word in program {
+        // zigzag encoding keeps small negative numbers small
+        let mut value = ((word << 1) ^ (word >> 63)) as u64;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+
+    writer.write_all(&bytes)
+}
+
+/// Binary format compressed with gzip, used for memory snapshots
+pub fn write_binary_gz(program: &[SIZE], writer: impl Write) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(writer, Compression::best());
+    write_binary(program, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text() {
+        assert_eq!(parse_text("1,0,0,3,99\n").unwrap(), vec![1, 0, 0, 3, 99]);
+        assert_eq!(
+            parse_text(" 1, 0 ,0,3,99 \r\n\n").unwrap(),
+            vec![1, 0, 0, 3, 99]
+        );
+        assert_eq!(
+            parse_text("# add\n1 0 0 3 ; ignored\n99\n").unwrap(),
+            vec![1, 0, 0, 3, 99]
+        );
+        assert_eq!(
+            parse_text("1101, 1, -1, 5,\n104, 0,\n99").unwrap(),
+            vec![1101, 1, -1, 5, 104, 0, 99]
+        );
+    }
+
+    #[test]
+    fn test_parse_text_errors() {
+        match parse_text("1,0\n2,x,3") {
+            Err(LoadError::InvalidNumber { line, token }) => {
+                assert_eq!(line, 2);
+                assert_eq!(token, "x");
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+
+        match parse_text("1,,2") {
+            Err(LoadError::EmptyValue { line }) => assert_eq!(line, 1),
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut program = vec![0, 1, -1, 63, -64, 64, 99];
+        program.extend(&[SIZE::MAX, SIZE::MIN]);
+
+        let mut bytes = Vec::new();
+        write_binary(&program, &mut bytes).unwrap();
+        assert!(bytes.starts_with(BINARY_MAGIC));
+        assert_eq!(&bytes[4..9], &[0, 2, 1, 126, 127]);
+        assert_eq!(load(&bytes).unwrap(), program);
+
+        let mut compressed = Vec::new();
+        write_binary_gz(&program, &mut compressed).unwrap();
+        assert!(compressed.starts_with(GZIP_MAGIC));
+        assert_eq!(load(&compressed).unwrap(), program);
+    }
+
+    #[test]
+    fn test_load_gzip_text() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"3,0,4,0,99\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(load(&compressed).unwrap(), vec![3, 0, 4, 0, 99]);
+    }
+
+    #[test]
+    fn test_truncated_binary() {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.push(0x80);
+        assert!(load(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_binary_overflow() {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend(&[0xff; 9]);
+        bytes.push(0x7f);
+        assert!(matches!(load(&bytes), Err(LoadError::VarintOverflow)));
+    }
+}
@@ -0,0 +1,747 @@
+use crate::intcode_computer::{
+    is_valid_instruction, parse_instruction, Opcode, ParamMode, State, CPU, SIZE,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug)]
+struct Instruction {
+    addr: usize,
+    opcode: Opcode,
+    modes: [ParamMode; 3],
+    len: usize,
+    /// Relative base the instruction runs with, set once it's known to be constant
+    base: SIZE,
+}
+
+impl Instruction {
+    fn param(&self, program: &[SIZE], index: usize) -> SIZE {
+        program[self.addr + 1 + index]
+    }
+
+    /// Address the parameter reads or writes, none for immediates
+    fn address(&self, program: &[SIZE], index: usize) -> Option<usize> {
+        let value = self.param(program, index);
+        let addr = match self.modes[index] {
+            ParamMode::Immediate => return None,
+            ParamMode::Position => value,
+            ParamMode::Relative => self.base.checked_add(value)?,
+        };
+        if addr < 0 {
+            return None;
+        }
+        Some(addr as usize)
+    }
+
+    fn is_write_param(&self, index: usize) -> bool {
+        match self.opcode {
+            Opcode::Add | Opcode::Multiply | Opcode::JmpLessThan | Opcode::JmpEquals => index == 2,
+            Opcode::Input => index == 0,
+            _ => false,
+        }
+    }
+
+    fn bytes(&self) -> std::ops::Range<usize> {
+        self.addr..self.addr + self.len
+    }
+
+    /// Only for jumps, whether the jump is always taken, never taken or unknown
+    fn condition(&self, program: &[SIZE]) -> Option<bool> {
+        if self.modes[0] != ParamMode::Immediate {
+            return None;
+        }
+        let value = self.param(program, 0);
+        match self.opcode {
+            Opcode::JmpTrue => Some(value != 0),
+            Opcode::JmpFalse => Some(value == 0),
+            _ => None,
+        }
+    }
+
+    fn is_jump(&self) -> bool {
+        self.opcode == Opcode::JmpTrue || self.opcode == Opcode::JmpFalse
+    }
+
+    /// Only for jumps, where they go when the target is an immediate address
+    fn target(&self, program: &[SIZE]) -> Option<usize> {
+        match self.modes[1] {
+            ParamMode::Immediate if self.param(program, 1) >= 0 => {
+                Some(self.param(program, 1) as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// Where the execution can go next, jumps to unknown targets are left out.
+    /// When `resolve` is set, jumps with an immediate condition only go where they're taken.
+    fn successors(&self, program: &[SIZE], resolve: bool) -> Vec<usize> {
+        let next = self.addr + self.len;
+        match self.opcode {
+            Opcode::Halt => Vec::new(),
+            Opcode::JmpTrue | Opcode::JmpFalse => {
+                let condition = if resolve {
+                    self.condition(program)
+                } else {
+                    None
+                };
+                let mut successors = Vec::new();
+                if condition != Some(true) {
+                    successors.push(next);
+                }
+                if let (Some(target), true) = (self.target(program), condition != Some(false)) {
+                    successors.push(target);
+                }
+                successors
+            }
+            _ => vec![next],
+        }
+    }
+}
+
+fn decode(program: &[SIZE], addr: usize) -> Option<Instruction> {
+    let instruction = *program.get(addr)?;
+    if !(0..100_000).contains(&instruction) || !is_valid_instruction(instruction) {
+        return None;
+    }
+
+    let (opcode, a, b, c) = parse_instruction(instruction);
+    let opcode = Opcode::from(opcode);
+    let instruction = Instruction {
+        addr,
+        opcode,
+        modes: [a, b, c],
+        len: opcode.param_count() + 1,
+        base: 0,
+    };
+
+    let writes_immediate = (0..opcode.param_count()).any(|index| {
+        instruction.is_write_param(index) && instruction.modes[index] == ParamMode::Immediate
+    });
+    if writes_immediate || addr + instruction.len > program.len() {
+        return None;
+    }
+
+    Some(instruction)
+}
+
+/// Every address the program reads or writes as data, when it can be known without running it
+#[derive(Default)]
+struct Accesses {
+    reads: HashSet<usize>,
+    writes: HashSet<usize>,
+}
+
+impl Accesses {
+    fn contains(&self, addr: usize) -> bool {
+        self.reads.contains(&addr) || self.writes.contains(&addr)
+    }
+}
+
+/// Follows the control flow from the first instruction.
+/// When `resolve` is set, jumps with an immediate condition only follow the branch that is taken.
+/// In strict mode it fails as soon as a jump target is unknown or something can't be decoded,
+/// otherwise it only skips what it can't follow.
+fn explore(program: &[SIZE], resolve: bool, strict: bool) -> Option<BTreeMap<usize, Instruction>> {
+    let mut instructions = BTreeMap::new();
+    let mut stack = vec![0];
+
+    while let Some(addr) = stack.pop() {
+        if instructions.contains_key(&addr) {
+            continue;
+        }
+        let instruction = match decode(program, addr) {
+            Some(instruction) => instruction,
+            None if strict => return None,
+            None => continue,
+        };
+
+        if strict && instruction.is_jump() && instruction.target(program).is_none() {
+            return None;
+        }
+        stack.extend(instruction.successors(program, resolve));
+        instructions.insert(addr, instruction);
+    }
+
+    Some(instructions)
+}
+
+/// Follows the relative base along the control flow, it has to be the same every time an
+/// instruction runs, so only immediate adjustments outside of loops can be followed.
+fn resolve_bases(program: &[SIZE], instructions: &mut BTreeMap<usize, Instruction>) -> Option<()> {
+    let mut bases: HashMap<usize, SIZE> = HashMap::new();
+    let mut stack = vec![(0, 0)];
+
+    while let Some((addr, base)) = stack.pop() {
+        match bases.insert(addr, base) {
+            Some(previous) if previous == base => continue,
+            Some(_) => return None,
+            None => (),
+        }
+        let instruction = instructions.get_mut(&addr)?;
+        instruction.base = base;
+
+        let next_base = match (instruction.opcode, instruction.modes[0]) {
+            (Opcode::SetRelativeBase, ParamMode::Immediate) => {
+                base.checked_add(instruction.param(program, 0))?
+            }
+            (Opcode::SetRelativeBase, _) => return None,
+            _ => base,
+        };
+        for next in instruction.successors(program, true) {
+            stack.push((next, next_base));
+        }
+    }
+
+    Some(())
+}
+
+/// Whether the instruction at `to` can run again once the one at `from` ran
+fn runs_after(
+    program: &[SIZE],
+    instructions: &BTreeMap<usize, Instruction>,
+    from: usize,
+    to: usize,
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = vec![from];
+
+    while let Some(addr) = stack.pop() {
+        let instruction = match instructions.get(&addr) {
+            Some(instruction) => instruction,
+            None => continue,
+        };
+        for next in instruction.successors(program, true) {
+            if next == to {
+                return true;
+            }
+            if visited.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+
+    false
+}
+
+/// Proves the reachable code is never modified before it runs and every data access is known.
+/// Jumps to computed addresses or a relative base that changes make that impossible,
+/// so those programs are refused.
+fn analyze(program: &[SIZE]) -> Option<(BTreeMap<usize, Instruction>, Accesses)> {
+    let mut instructions = explore(program, true, true)?;
+    resolve_bases(program, &mut instructions)?;
+    let mut accesses = Accesses::default();
+    // byte of code -> instruction it belongs to
+    let mut code = HashMap::new();
+    // (writer, written address)
+    let mut writes = Vec::new();
+
+    for instruction in instructions.values() {
+        for addr in instruction.bytes() {
+            if code.insert(addr, instruction.addr).is_some() {
+                // overlapping instructions
+                return None;
+            }
+        }
+
+        for index in 0..instruction.opcode.param_count() {
+            if instruction.modes[index] == ParamMode::Immediate {
+                continue;
+            }
+            let addr = instruction.address(program, index)?;
+            if instruction.is_write_param(index) {
+                accesses.writes.insert(addr);
+                writes.push((instruction.addr, addr));
+            } else {
+                accesses.reads.insert(addr);
+            }
+        }
+    }
+
+    // writing over code is fine as long as that code never runs again, like day 2 does
+    let modified = writes.iter().any(|(writer, addr)| {
+        code.get(addr)
+            .is_some_and(|written| runs_after(program, &instructions, *writer, *written))
+    });
+    if modified {
+        return None;
+    }
+
+    Some((instructions, accesses))
+}
+
+type Pass = fn(&mut [SIZE], &BTreeMap<usize, Instruction>, &Accesses);
+
+/// The entry and every jump target, the only places reached from more than one instruction.
+/// What follows a jump is only reached by falling through it, so it doesn't start a block.
+fn block_starts(program: &[SIZE], instructions: &BTreeMap<usize, Instruction>) -> HashSet<usize> {
+    let mut starts = HashSet::new();
+    starts.insert(0);
+    for instruction in instructions.values().filter(|i| i.is_jump()) {
+        if instruction.modes[1] == ParamMode::Immediate {
+            starts.insert(instruction.param(program, 1) as usize);
+        }
+    }
+    starts
+}
+
+fn encode(opcode: SIZE, modes: &[ParamMode]) -> SIZE {
+    modes
+        .iter()
+        .enumerate()
+        .fold(opcode, |instruction, (index, mode)| {
+            let digit = match mode {
+                ParamMode::Position => 0,
+                ParamMode::Immediate => 1,
+                ParamMode::Relative => 2,
+            };
+            instruction + digit * 10_i64.pow(index as u32 + 2)
+        })
+}
+
+/// Inside a basic block, the values written by earlier instructions are known, so reading
+/// them becomes an immediate parameter and Add/Multiply/LessThan/Equals on immediates only
+/// are folded into a single constant store.
+fn fold_constants(
+    program: &mut [SIZE],
+    instructions: &BTreeMap<usize, Instruction>,
+    accesses: &Accesses,
+) {
+    let starts = block_starts(program, instructions);
+    let mut known: HashMap<usize, SIZE> = HashMap::new();
+    let mut fallthrough = None;
+
+    for instruction in instructions.values() {
+        if starts.contains(&instruction.addr) || fallthrough != Some(instruction.addr) {
+            known.clear();
+        }
+        fallthrough = Some(instruction.addr + instruction.len);
+
+        let count = instruction.opcode.param_count();
+        let mut modes = instruction.modes;
+        let mut params: Vec<SIZE> = (0..count).map(|i| instruction.param(program, i)).collect();
+        let written = (0..count)
+            .find(|index| instruction.is_write_param(*index))
+            .and_then(|index| instruction.address(program, index));
+        for index in 0..count {
+            if instruction.is_write_param(index) {
+                continue;
+            }
+            // what nothing ever writes keeps the value it starts with
+            let value = instruction.address(program, index).and_then(|address| {
+                known.get(&address).copied().or_else(|| {
+                    if accesses.writes.contains(&address) {
+                        None
+                    } else {
+                        Some(program.get(address).copied().unwrap_or(0))
+                    }
+                })
+            });
+            if let Some(value) = value {
+                modes[index] = ParamMode::Immediate;
+                params[index] = value;
+            }
+        }
+
+        let immediates =
+            count >= 2 && modes[0] == ParamMode::Immediate && modes[1] == ParamMode::Immediate;
+        let (a, b) = (params.first().copied(), params.get(1).copied());
+        let value = match (instruction.opcode, a, b) {
+            _ if !immediates => None,
+            (Opcode::Add, Some(a), Some(b)) => a.checked_add(b),
+            (Opcode::Multiply, Some(a), Some(b)) => a.checked_mul(b),
+            (Opcode::JmpLessThan, Some(a), Some(b)) => Some(if a < b { 1 } else { 0 }),
+            (Opcode::JmpEquals, Some(a), Some(b)) => Some(if a == b { 1 } else { 0 }),
+            _ => None,
+        };
+
+        let addr = instruction.addr;
+        let mut rewritten = program[instruction.bytes()].to_vec();
+        if let Some(value) = value {
+            let destination = [ParamMode::Immediate, ParamMode::Immediate, modes[2]];
+            rewritten[0] = encode(1, &destination);
+            rewritten[1] = value;
+            rewritten[2] = 0;
+        } else {
+            rewritten[0] = encode(program[addr] % 100, &modes[..count]);
+            rewritten[1..].copy_from_slice(&params);
+        }
+        // bytes the program reads or writes as data have to stay as they are
+        let fixed = instruction
+            .bytes()
+            .zip(rewritten.iter())
+            .any(|(addr, byte)| program[addr] != *byte && accesses.contains(addr));
+        if !fixed {
+            program[instruction.bytes()].copy_from_slice(&rewritten);
+        }
+
+        match (written, value) {
+            (Some(dest), Some(value)) => {
+                known.insert(dest, value);
+            }
+            (Some(dest), None) => {
+                known.remove(&dest);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Jumps that are never taken do nothing. Jumps into them go past them instead,
+/// and falling into two or more of them in a row jumps over them.
+fn skip_useless(
+    program: &mut [SIZE],
+    instructions: &BTreeMap<usize, Instruction>,
+    accesses: &Accesses,
+) {
+    let useless = |instruction: &Instruction| {
+        !instruction.bytes().any(|addr| accesses.contains(addr))
+            && instruction.is_jump()
+            && instruction.condition(program) == Some(false)
+    };
+
+    // (start, end, instruction count)
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+    for instruction in instructions.values().filter(|i| useless(i)) {
+        match runs.last_mut() {
+            Some(run) if run.1 == instruction.addr => {
+                run.1 += instruction.len;
+                run.2 += 1;
+            }
+            _ => runs.push((instruction.addr, instruction.addr + instruction.len, 1)),
+        }
+    }
+    runs.retain(|(_, end, _)| instructions.contains_key(end));
+
+    for instruction in instructions.values() {
+        let target_addr = instruction.addr + 2;
+        if !instruction.is_jump()
+            || instruction.modes[1] != ParamMode::Immediate
+            || accesses.contains(target_addr)
+        {
+            continue;
+        }
+        let target = program[target_addr] as usize;
+        if let Some((_, end, _)) = runs
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&target))
+        {
+            program[target_addr] = *end as SIZE;
+        }
+    }
+
+    for (start, end, _) in runs.iter().filter(|(_, _, count)| *count >= 2) {
+        program[*start..*start + 3].copy_from_slice(&[1105, 1, *end as SIZE]);
+    }
+}
+
+fn thread_jumps(
+    program: &mut [SIZE],
+    instructions: &BTreeMap<usize, Instruction>,
+    accesses: &Accesses,
+) {
+    for instruction in instructions.values() {
+        let target_addr = instruction.addr + 2;
+        if !instruction.is_jump()
+            || instruction.modes[1] != ParamMode::Immediate
+            || instruction.condition(program) == Some(false)
+            || accesses.contains(target_addr)
+        {
+            continue;
+        }
+
+        let mut target = program[target_addr] as usize;
+        let mut visited = HashSet::new();
+        while visited.insert(target) {
+            match instructions.get(&target) {
+                Some(jump)
+                    if jump.is_jump()
+                        && jump.modes[1] == ParamMode::Immediate
+                        && jump.condition(program) == Some(true) =>
+                {
+                    target = jump.param(program, 1) as usize;
+                }
+                _ => break,
+            }
+        }
+
+        program[target_addr] = target as SIZE;
+    }
+}
+
+/// Clears instructions that can no longer be reached and that are never read as data
+fn clear_dead_code(original: &[SIZE], program: &mut [SIZE], accesses: &Accesses) {
+    let live: HashSet<usize> = explore(program, true, true)
+        .expect("Optimized program should still be analyzable")
+        .values()
+        .flat_map(|instruction| instruction.bytes())
+        .collect();
+
+    let candidates = explore(original, false, false).unwrap_or_default();
+    for instruction in candidates.values() {
+        for addr in instruction.bytes() {
+            if !live.contains(&addr) && !accesses.contains(addr) {
+                program[addr] = 0;
+            }
+        }
+    }
+}
+
+/// Rewrites the program into one that gives the same outputs and leaves the same data in
+/// memory for the same inputs while executing less instructions. Only code the program never
+/// reads or writes as data changes.
+/// Addresses are kept as they are so data references stay valid.
+/// Programs that could modify code that runs later are returned unchanged.
+pub fn optimize(program: &[SIZE]) -> Vec<SIZE> {
+    let mut optimized = program.to_owned();
+    let passes: [Pass; 3] = [fold_constants, skip_useless, thread_jumps];
+
+    for pass in passes.iter() {
+        // every pass changes what the next one can prove
+        let (instructions, accesses) = match analyze(&optimized) {
+            Some(analysis) => analysis,
+            None => return optimized,
+        };
+        pass(&mut optimized, &instructions, &accesses);
+    }
+
+    let accesses = match analyze(&optimized) {
+        Some((_, accesses)) => accesses,
+        None => return optimized,
+    };
+    clear_dead_code(program, &mut optimized, &accesses);
+
+    optimized
+}
+
+/// Outputs, memory where the program stopped and how many instructions it executed
+fn run_trace(program: &[SIZE], trace: &[SIZE]) -> (Vec<SIZE>, Vec<SIZE>, usize) {
+    let mut cpu = CPU::new(program.to_owned());
+    cpu.input.extend(trace);
+
+    let mut steps = 0;
+    loop {
+        steps += 1;
+        match cpu.step() {
+            State::Halt | State::Input => break,
+            _ => (),
+        }
+    }
+
+    (cpu.output, cpu.memory, steps)
+}
+
+/// Runs both programs with every recorded input trace and compares the outputs and the
+/// final memory, except for the code that was rewritten.
+pub fn verify(original: &[SIZE], optimized: &[SIZE], traces: &[Vec<SIZE>]) -> bool {
+    let rewritten: HashSet<usize> = (0..original.len().max(optimized.len()))
+        .filter(|addr| original.get(*addr) != optimized.get(*addr))
+        .collect();
+
+    traces.iter().all(|trace| {
+        let (outputs, memory, _) = run_trace(original, trace);
+        let (optimized_outputs, optimized_memory, _) = run_trace(optimized, trace);
+        let len = memory.len().max(optimized_memory.len());
+        outputs == optimized_outputs
+            && (0..len)
+                .filter(|addr| !rewritten.contains(addr))
+                .all(|addr| {
+                    memory.get(addr).unwrap_or(&0) == optimized_memory.get(addr).unwrap_or(&0)
+                })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode_computer::parse_input;
+
+    #[test]
+    fn test_optimize() {
+        #[rustfmt::skip]
+        let program = vec![
+            3, 40,            // in [40]
+            1102, 6, 7, 41,   // [41] = 6 * 7
+            1008, 41, 42, 42, // [42] = [41] == 42
+            1007, 41, 10, 43, // [43] = [41] < 10
+            1006, 42, 26,     // if [42] == 0 goto 26
+            1005, 43, 26,     // if [43] != 0 goto 26
+            1005, 40, 29,     // if [40] != 0 goto 29
+            104, 0,           // out 0
+            99,
+            104, -1,          // out -1
+            99,
+            1105, 1, 32,      // goto 32
+            4, 41,            // out [41]
+            99,
+        ];
+
+        let optimized = optimize(&program);
+
+        // both checks are known to pass, so they're jumped over
+        #[rustfmt::skip]
+        let expected = vec![
+            3, 40,
+            1101, 42, 0, 41,
+            1101, 1, 0, 42,
+            1101, 0, 0, 43,
+            1105, 1, 20,
+            0, 0, 0,
+            1005, 40, 32,
+            104, 0,
+            99,
+            0, 0,
+            0,
+            0, 0, 0,
+            4, 41,
+            99,
+        ];
+        assert_eq!(optimized, expected);
+
+        let traces = vec![vec![0], vec![1], vec![-5]];
+        assert!(verify(&program, &optimized, &traces));
+        assert_eq!(run_trace(&program, &[0]).2, 9);
+        assert_eq!(run_trace(&optimized, &[0]).2, 8);
+        assert_eq!(run_trace(&program, &[1]).2, 10);
+        assert_eq!(run_trace(&optimized, &[1]).2, 8);
+
+        // dropping the store to [42] gives the same outputs but not the same memory
+        let mut without_store = optimized.clone();
+        without_store[6..10].copy_from_slice(&[1105, 1, 10, 0]);
+        assert!(!verify(&program, &without_store, &traces));
+    }
+
+    #[test]
+    fn test_propagate() {
+        #[rustfmt::skip]
+        let program = vec![
+            3, 40,           // in [40]
+            1107, 3, 5, 41,  // [41] = 3 < 5
+            1002, 41, 7, 46, // [46] = [41] * 7
+            1005, 46, 16,    // if [46] != 0 goto 16
+            104, 0,          // out 0
+            99,
+            1102, 6, 7, 42,  // [42] = 6 * 7
+            1, 42, 40, 43,   // [43] = [42] + [40]
+            4, 43,           // out [43]
+            99,
+        ];
+
+        let optimized = optimize(&program);
+
+        // the stores stay, the data they leave in memory is part of the result
+        #[rustfmt::skip]
+        let expected = vec![
+            3, 40,
+            1101, 1, 0, 41,
+            1101, 7, 0, 46,
+            1105, 7, 16,
+            0, 0,
+            0,
+            1101, 42, 0, 42,
+            101, 42, 40, 43,
+            4, 43,
+            99,
+        ];
+        assert_eq!(optimized, expected);
+        assert!(verify(&program, &optimized, &[vec![0], vec![5], vec![-3]]));
+    }
+
+    #[test]
+    fn test_day2() {
+        let input = parse_input(
+            std::fs::read_to_string("input/2019/day2.txt")
+                .unwrap()
+                .trim(),
+        );
+        // the noun and verb of both parts, the answer is what ends up at address 0
+        for &(noun, verb, answer) in &[(12, 2, 3_224_742), (79, 60, 19_690_720)] {
+            let mut program = input.clone();
+            program[1] = noun;
+            program[2] = verb;
+
+            let optimized = optimize(&program);
+            assert_ne!(optimized, program);
+            assert!(verify(&program, &optimized, &[vec![]]));
+            assert_eq!(run_trace(&optimized, &[]).1[0], answer);
+        }
+    }
+
+    #[test]
+    fn test_real_traces() {
+        // inputs given by days 5, 7 and 9, none of those programs can be proven safe
+        let days = vec![
+            ("input/2019/day5.txt", vec![vec![1], vec![5]]),
+            (
+                "input/2019/day7.txt",
+                vec![vec![0, 0], vec![3, 12], vec![4, 1]],
+            ),
+            ("input/2019/day9.txt", vec![vec![1]]),
+        ];
+        for (path, traces) in days {
+            let program = parse_input(std::fs::read_to_string(path).unwrap().trim());
+            let optimized = optimize(&program);
+            assert_eq!(optimized, program, "{}", path);
+            assert!(verify(&program, &optimized, &traces), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_self_modifying() {
+        #[rustfmt::skip]
+        let program = vec![
+            1101, 0, 9, 6,   // change the target of the next jump to 9
+            1105, 1, 7,
+            104, 1,
+            1102, 2, 3, 20,
+            4, 20,
+            99,
+        ];
+
+        assert_eq!(optimize(&program), program);
+    }
+
+    #[test]
+    fn test_constant_base() {
+        #[rustfmt::skip]
+        let program = vec![
+            109, 20,             // rb = 20
+            21101, 6, 7, 0,      // [rb] = 6 + 7
+            203, 1,              // in [rb + 1]
+            22201, 0, 1, 2,      // [rb + 2] = [rb] + [rb + 1]
+            204, 2,              // out [rb + 2]
+            99,
+        ];
+
+        let optimized = optimize(&program);
+
+        #[rustfmt::skip]
+        let expected = vec![
+            109, 20,
+            21101, 13, 0, 0,
+            203, 1,
+            22101, 13, 1, 2,
+            204, 2,
+            99,
+        ];
+        assert_eq!(optimized, expected);
+        assert!(verify(&program, &optimized, &[vec![0], vec![29]]));
+    }
+
+    #[test]
+    fn test_relative_mode() {
+        // the quine adjusts the relative base every time it loops
+        let program = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+
+        assert_eq!(optimize(&program), program);
+    }
+
+    #[test]
+    fn test_read_as_data() {
+        // outputs the first value of the multiplication so it can't be folded,
+        // nothing writes that value so the output reads it as an immediate instead
+        let program = vec![1102, 6, 7, 8, 4, 1, 99, 0, 0];
+
+        assert_eq!(optimize(&program), vec![1102, 6, 7, 8, 104, 6, 99, 0, 0]);
+    }
+}
@@ -3,6 +3,8 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+pub mod answers;
+pub mod compression;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -16,7 +18,29 @@ pub mod day10;
 pub mod day11;
 pub mod day12;
 pub mod day13;
+pub mod day14;
+pub mod day14_analysis;
+pub mod day15;
+pub mod day15_visualization;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day21_synthesis;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 
-mod intcode_computer;
+pub mod intcode_async;
+pub mod intcode_computer;
+pub mod intcode_debugger;
+pub mod intcode_loader;
+pub mod intcode_optimizer;
+
+pub mod runner;
+pub mod scaffold;
 
 aoc_lib! { year = 2019 }
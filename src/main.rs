@@ -1,3 +1,141 @@
-use aoc_runner_derive::aoc_main;
+use advent_of_code_2019::answers::{self, Answers, Verdict};
+use advent_of_code_2019::runner::{parse_range, run_selected, summary_table, Outcome, Selection};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
 
-aoc_main! { lib = advent_of_code_2019 }
+const USAGE: &str = "usage: advent_of_code_2019 [options] [days] [parts]
+
+Days and parts are a number or a range like 1-5, 20- or -3, every one by default
+
+options:
+    --input <dir>   directory with the dayN.txt inputs, input/2019 by default
+    --alt <name>    only run the alternative with that name
+    --main          only run the solutions without a name
+    --no-summary    don't print the summary table
+    --check         compare every answer with the known answers, fails on any difference
+    --record        save the answers of the main solutions as the known answers
+    --answers <file>  known answers, answers/2019.txt by default";
+
+fn usage_error() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn main() {
+    let mut selection = Selection::default();
+    let mut directory = PathBuf::from("input/2019");
+    let mut summary = true;
+    let mut check = false;
+    let mut record = false;
+    let mut answers_path = answers::path(2019);
+    let mut ranges = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => directory = args.next().unwrap_or_else(|| usage_error()).into(),
+            "--alt" => {
+                selection.alternative = Some(Some(args.next().unwrap_or_else(|| usage_error())))
+            }
+            "--main" => selection.alternative = Some(None),
+            "--no-summary" => summary = false,
+            "--check" => check = true,
+            "--record" => record = true,
+            "--answers" => answers_path = args.next().unwrap_or_else(|| usage_error()).into(),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => ranges.push(arg),
+        }
+    }
+
+    match ranges.as_slice() {
+        [] => (),
+        [days] => selection.days = parse_range(days, 1..=25).unwrap_or_else(|| usage_error()),
+        [days, parts] => {
+            selection.days = parse_range(days, 1..=25).unwrap_or_else(|| usage_error());
+            selection.parts = parse_range(parts, 1..=2).unwrap_or_else(|| usage_error());
+        }
+        _ => usage_error(),
+    }
+
+    println!("Advent of code 2019");
+    let outcomes = run_selected(&selection, &directory, |outcome| match &outcome.answer {
+        Ok(_) => println!("{}", outcome),
+        Err(_) => eprintln!("{}", outcome),
+    });
+
+    if summary {
+        println!();
+        print!("{}", summary_table(&outcomes));
+    }
+
+    let result = if record {
+        record_answers(&answers_path, &outcomes)
+    } else if check {
+        check_answers(&answers_path, &outcomes)
+    } else {
+        Ok(true)
+    };
+    match result {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("{}: {}", answers_path.display(), err);
+            process::exit(1);
+        }
+    }
+}
+
+fn load_answers(path: &Path) -> io::Result<Answers> {
+    let input = match fs::read_to_string(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Answers::default()),
+        input => input?,
+    };
+    Answers::parse(&input).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// False when any answer is different from the known one
+fn check_answers(path: &Path, outcomes: &[Outcome]) -> io::Result<bool> {
+    let answers = load_answers(path)?;
+    let (mut correct, mut wrong, mut unknown) = (0, 0, 0);
+
+    println!();
+    for outcome in outcomes {
+        match answers.check(outcome) {
+            Verdict::Correct => correct += 1,
+            Verdict::Wrong { expected } => {
+                wrong += 1;
+                println!("{}\n\texpected: {}", outcome, expected);
+            }
+            Verdict::Unknown => unknown += 1,
+        }
+    }
+    println!(
+        "{} correct, {} wrong, {} without a known answer",
+        correct, wrong, unknown
+    );
+    Ok(wrong == 0)
+}
+
+fn record_answers(path: &Path, outcomes: &[Outcome]) -> io::Result<bool> {
+    let mut answers = load_answers(path)?;
+    let changed = answers.record(outcomes);
+
+    println!();
+    for outcome in &changed {
+        println!("recorded {}", outcome);
+    }
+    if !changed.is_empty() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, answers.to_string())?;
+    }
+    println!("{} answers in {}", answers.len(), path.display());
+    Ok(true)
+}
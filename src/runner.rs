@@ -0,0 +1,451 @@
+use aoc_runner::{ArcStr, Runner};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// `aoc_lib!` puts a trait with one factory method per `#[aoc]` function in there
+use crate::aoc_factory::*;
+
+type Generate = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// One `#[aoc]` function, with the generator of its day
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day: u32,
+    pub part: u32,
+    /// Alternatives have a name, like `map` in `#[aoc(day1, part1, map)]`
+    pub name: Option<&'static str>,
+    generate: Generate,
+}
+
+impl Solution {
+    /// Parses a factory method name like `day1_part1_map`
+    fn new(method: &'static str, generate: Generate) -> Self {
+        let parse = || {
+            let rest = method.strip_prefix("day")?;
+            let (day, rest) = rest.split_at(rest.find('_')?);
+            let rest = rest.strip_prefix("_part")?;
+            let end = rest.find('_').unwrap_or(rest.len());
+            let name = rest.get(end + 1..).filter(|name| !name.is_empty());
+            Some((day.parse().ok()?, rest[..end].parse().ok()?, name))
+        };
+        let (day, part, name) = parse().expect("Factory methods are named dayN_partN[_name]");
+        Solution {
+            day,
+            part,
+            name,
+            generate,
+        }
+    }
+
+    /// Runs the generator then the solver on that input, timing both
+    pub fn run(&self, input: &str) -> Outcome {
+        let mut outcome = Outcome {
+            day: self.day,
+            part: self.part,
+            name: self.name,
+            generator: Duration::default(),
+            solver: None,
+            answer: Err(Failure::Generating(String::new())),
+        };
+
+        let start = Instant::now();
+        let runner = catch(|| (self.generate)(ArcStr::from(input)));
+        outcome.generator = start.elapsed();
+        let runner = match runner {
+            Ok(runner) => runner,
+            Err(err) => {
+                outcome.answer = Err(Failure::Generating(err));
+                return outcome;
+            }
+        };
+
+        let start = Instant::now();
+        let answer = catch(|| runner.try_run().map(|answer| answer.to_string()));
+        outcome.solver = Some(start.elapsed());
+        outcome.answer = answer.map_err(Failure::Running);
+        outcome
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        if let Some(name) = self.name {
+            write!(f, " - {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors and panics both become the message of the failure
+fn catch<T>(f: impl FnOnce() -> Result<T, Box<dyn Error>>) -> Result<T, String> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result.map_err(|err| err.to_string()),
+        Err(payload) => Err(payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("panicked"))),
+    }
+}
+
+macro_rules! solutions {
+    ($($method:ident),* $(,)?) => {
+        /// Every solution, sorted by day, part and then name
+        pub fn solutions() -> Vec<Solution> {
+            let mut solutions = vec![$(Solution::new(stringify!($method), Factory::$method)),*];
+            solutions.sort_by_key(|s| (s.day, s.part, s.name));
+            solutions
+        }
+    };
+}
+
+solutions! {
+    day1_part1, day1_part1_map, day1_part2, day1_part2_map,
+    day2_part1, day2_part2,
+    day3_part1, day3_part2,
+    day4_part1, day4_part2,
+    day5_part1, day5_part2,
+    day6_part1, day6_part2,
+    day7_part1, day7_part2, day7_part2_asynchronous,
+    day8_part1, day8_part2,
+    day9_part1, day9_part2,
+    day10_part1, day10_part2,
+    day11_part1, day11_part2,
+    day12_part1, day12_part2,
+    day13_part1, day13_part2, day13_part2_framebuffer,
+    day14_part1, day14_part2,
+    day15_part1, day15_part2,
+    day16_part1, day16_part1_prefix_sums, day16_part2,
+    day17_part1, day17_part2,
+    day18_part1, day18_part2,
+    day19_part1, day19_part2,
+    day20_part1, day20_part2,
+    day21_part1, day21_part1_synthesized, day21_part2, day21_part2_synthesized,
+    day22_part1, day22_part2,
+    day23_part1, day23_part2,
+    day24_part1, day24_part2,
+    day25_part1,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Reading(String),
+    Generating(String),
+    Running(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Reading(err) => write!(f, "FAILED while reading the input: {}", err),
+            Failure::Generating(err) => write!(f, "FAILED while generating: {}", err),
+            Failure::Running(err) => write!(f, "FAILED while running: {}", err),
+        }
+    }
+}
+
+/// What happened when running a solution, the solver time is None when it never ran
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub day: u32,
+    pub part: u32,
+    pub name: Option<&'static str>,
+    pub generator: Duration,
+    pub solver: Option<Duration>,
+    pub answer: Result<String, Failure>,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        if let Some(name) = self.name {
+            write!(f, " - {}", name)?;
+        }
+        match &self.answer {
+            Ok(answer) => write!(f, ": {}", answer),
+            Err(failure) => write!(f, ": {}", failure),
+        }
+    }
+}
+
+/// Which solutions to run, both ranges are inclusive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub days: RangeInclusive<u32>,
+    pub parts: RangeInclusive<u32>,
+    /// Every solution when None, `Some(None)` keeps only the ones without a name
+    pub alternative: Option<Option<String>>,
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection {
+            days: 1..=25,
+            parts: 1..=2,
+            alternative: None,
+        }
+    }
+}
+
+impl Selection {
+    pub fn contains(&self, solution: &Solution) -> bool {
+        self.days.contains(&solution.day)
+            && self.parts.contains(&solution.part)
+            && match &self.alternative {
+                None => true,
+                Some(name) => name.as_deref() == solution.name,
+            }
+    }
+}
+
+/// Parses `3`, `1-5`, `20-` or `-4`
+pub fn parse_range(text: &str, full: RangeInclusive<u32>) -> Option<RangeInclusive<u32>> {
+    let bound = |text: &str, default| match text {
+        "" => Some(default),
+        _ => text.parse().ok(),
+    };
+    let (start, end) = match text.find('-') {
+        Some(i) => (
+            bound(&text[..i], *full.start())?,
+            bound(&text[i + 1..], *full.end())?,
+        ),
+        None => {
+            let n = text.parse().ok()?;
+            (n, n)
+        }
+    };
+    if start > end || !full.contains(&start) || !full.contains(&end) {
+        return None;
+    }
+    Some(start..=end)
+}
+
+pub fn read_input(directory: &Path, day: u32) -> io::Result<String> {
+    fs::read_to_string(directory.join(format!("day{}.txt", day)))
+}
+
+/// Runs every selected solution, reading each day's input once. Calls
+/// `report` as soon as a solution is done.
+pub fn run_selected(
+    selection: &Selection,
+    directory: &Path,
+    mut report: impl FnMut(&Outcome),
+) -> Vec<Outcome> {
+    let mut outcomes = Vec::new();
+    let mut input = None;
+
+    for solution in solutions().iter().filter(|s| selection.contains(s)) {
+        match &input {
+            Some((day, _)) if *day == solution.day => (),
+            _ => input = Some((solution.day, read_input(directory, solution.day))),
+        }
+        let outcome = match &input {
+            Some((_, Ok(input))) => solution.run(input),
+            Some((_, Err(err))) => Outcome {
+                day: solution.day,
+                part: solution.part,
+                name: solution.name,
+                generator: Duration::default(),
+                solver: None,
+                answer: Err(Failure::Reading(err.to_string())),
+            },
+            None => unreachable!(),
+        };
+        report(&outcome);
+        outcomes.push(outcome);
+    }
+    outcomes
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", micros as f64 / 1e3)
+    } else {
+        format!("{:.2}s", micros as f64 / 1e6)
+    }
+}
+
+/// One row per outcome with both timings, multi-line answers are shortened
+pub fn summary_table(outcomes: &[Outcome]) -> String {
+    let header = ["Day", "Part", "Name", "Generator", "Solver", "Answer"];
+    let mut rows: Vec<Vec<String>> = vec![header.iter().map(|h| h.to_string()).collect()];
+
+    for outcome in outcomes {
+        let answer = match &outcome.answer {
+            Ok(answer) if answer.lines().count() > 1 => {
+                format!("({} lines)", answer.lines().count())
+            }
+            Ok(answer) => answer.clone(),
+            Err(Failure::Reading(_)) => String::from("FAILED reading"),
+            Err(Failure::Generating(_)) => String::from("FAILED generating"),
+            Err(Failure::Running(_)) => String::from("FAILED running"),
+        };
+        rows.push(vec![
+            outcome.day.to_string(),
+            outcome.part.to_string(),
+            outcome.name.unwrap_or("").to_owned(),
+            format_duration(outcome.generator),
+            outcome.solver.map_or(String::from("-"), format_duration),
+            answer,
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let total: Duration = outcomes
+        .iter()
+        .map(|outcome| outcome.generator + outcome.solver.unwrap_or_default())
+        .sum();
+
+    let mut table = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        table.push_str(cells.join(" | ").trim_end());
+        table.push('\n');
+        if i == 0 {
+            let rules: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            table.push_str(&rules.join("-+-"));
+            table.push('\n');
+        }
+    }
+    table.push_str(&format!("Total: {}\n", format_duration(total)));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(day: u32, part: u32, name: Option<&str>) -> Solution {
+        solutions()
+            .into_iter()
+            .find(|s| s.day == day && s.part == part && s.name == name)
+            .unwrap()
+    }
+
+    /// Every `#[aoc(dayN, partN[, name])]` attribute in `src/`, the templates aside
+    fn aoc_attributes() -> Vec<(u32, u32, Option<String>)> {
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut attributes = Vec::new();
+        for entry in std::fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            if !file_name.ends_with(".rs") || file_name.starts_with("template") {
+                continue;
+            }
+            for line in std::fs::read_to_string(&path).unwrap().lines() {
+                let args = match line.trim().strip_prefix("#[aoc(") {
+                    Some(args) => args.trim_end_matches(")]"),
+                    None => continue,
+                };
+                let args: Vec<&str> = args.split(',').map(str::trim).collect();
+                let number = |arg: &str, prefix| arg.strip_prefix(prefix)?.parse().ok();
+                let (day, part) = match (number(args[0], "day"), number(args[1], "part")) {
+                    (Some(day), Some(part)) => (day, part),
+                    _ => panic!("{}: unexpected attribute {}", path.display(), line),
+                };
+                attributes.push((day, part, args.get(2).map(|name| name.to_lowercase())));
+            }
+        }
+        attributes.sort();
+        attributes
+    }
+
+    #[test]
+    fn test_solutions() {
+        let solutions = solutions();
+        let registered: Vec<_> = solutions
+            .iter()
+            .map(|s| (s.day, s.part, s.name.map(String::from)))
+            .collect();
+        assert_eq!(registered, aoc_attributes());
+        assert_eq!(
+            find(16, 1, Some("prefix_sums")).to_string(),
+            "Day 16 - Part 1 - prefix_sums"
+        );
+        assert!(solutions
+            .windows(2)
+            .all(|w| (w[0].day, w[0].part, w[0].name) < (w[1].day, w[1].part, w[1].name)));
+    }
+
+    #[test]
+    fn test_run() {
+        let outcome = find(1, 1, Some("map")).run("12\n14\n1969");
+        assert_eq!(outcome.answer, Ok(String::from("658")));
+        assert!(outcome.solver.is_some());
+
+        let outcome = find(22, 1, None).run("");
+        assert!(matches!(outcome.answer, Err(Failure::Generating(_))));
+        assert_eq!(outcome.solver, None);
+
+        let outcome = find(1, 1, None).run("12\nfourteen");
+        assert!(matches!(outcome.answer, Err(Failure::Running(_))));
+    }
+
+    #[test]
+    fn test_selection() {
+        assert_eq!(parse_range("3", 1..=25), Some(3..=3));
+        assert_eq!(parse_range("1-5", 1..=25), Some(1..=5));
+        assert_eq!(parse_range("20-", 1..=25), Some(20..=25));
+        assert_eq!(parse_range("-2", 1..=2), Some(1..=2));
+        assert_eq!(parse_range("5-1", 1..=25), None);
+        assert_eq!(parse_range("26", 1..=25), None);
+
+        let selection = Selection {
+            days: 1..=2,
+            parts: 1..=1,
+            alternative: Some(None),
+        };
+        let selected: Vec<String> = solutions()
+            .iter()
+            .filter(|s| selection.contains(s))
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(selected, vec!["Day 1 - Part 1", "Day 2 - Part 1"]);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let outcome = |part, answer| Outcome {
+            day: 8,
+            part,
+            name: None,
+            generator: Duration::from_micros(12),
+            solver: Some(Duration::from_millis(3)),
+            answer,
+        };
+        let table = summary_table(&[
+            outcome(1, Ok(String::from("1320"))),
+            outcome(2, Ok(String::from("#..\n.#.\n..#"))),
+        ]);
+        assert_eq!(
+            table,
+            "Day | Part | Name | Generator | Solver | Answer
+----+------+------+-----------+--------+----------
+8   | 1    |      | 12µs      | 3.00ms | 1320
+8   | 2    |      | 12µs      | 3.00ms | (3 lines)
+Total: 6.02ms
+"
+        );
+    }
+}
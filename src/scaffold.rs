@@ -0,0 +1,306 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const PLACEHOLDER: &str = "dayX";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// Solutions that take the raw `&str` input
+    Plain,
+    /// Solutions that take a program parsed with `parse_input`
+    Intcode,
+}
+
+impl Template {
+    pub fn source(self) -> &'static str {
+        match self {
+            Template::Plain => include_str!("template.rs"),
+            Template::Intcode => include_str!("template_intcode.rs"),
+        }
+    }
+
+    /// The template with every `dayX` replaced by the day used in `#[aoc]` attributes
+    pub fn render(self, day: u32) -> String {
+        self.source().replace(PLACEHOLDER, &format!("day{}", day))
+    }
+}
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    InvalidDay(u32),
+    ModuleExists(PathBuf),
+    AlreadyRegistered(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScaffoldError::InvalidDay(day) => write!(f, "There is no day {} in the calendar", day),
+            ScaffoldError::ModuleExists(path) => write!(f, "{} already exists", path.display()),
+            ScaffoldError::AlreadyRegistered(name) => write!(f, "{} is already registered", name),
+            ScaffoldError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for ScaffoldError {}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(err: io::Error) -> Self {
+        ScaffoldError::Io(err)
+    }
+}
+
+pub fn module_name(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+/// Adds `pub mod <module>;` to the list of day modules, keeping it sorted.
+/// None when the module is already declared.
+pub fn register_module(lib: &str, module: &str) -> Option<String> {
+    let declaration = format!("pub mod {};", module);
+    let mut lines: Vec<&str> = lib.lines().collect();
+    if lines.iter().any(|line| line.trim() == declaration) {
+        return None;
+    }
+
+    let days: Vec<(usize, &str)> = lines
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, line)| line.starts_with("pub mod day"))
+        .collect();
+    let index = match days.iter().find(|(_, line)| *line > declaration.as_str()) {
+        Some((i, _)) => *i,
+        None => days.last().map_or(lines.len(), |(i, _)| i + 1),
+    };
+    lines.insert(index, &declaration);
+
+    let mut lib = lines.join("\n");
+    lib.push('\n');
+    Some(lib)
+}
+
+/// Adds the part 1 factory of that day to the `solutions!` list of the runner.
+/// None when it's already there or when there is no list.
+pub fn register_solution(runner: &str, day: u32) -> Option<String> {
+    let entry = format!("day{}_part1", day);
+    let mut lines: Vec<&str> = runner.lines().collect();
+    let start = lines.iter().position(|line| *line == "solutions! {")?;
+    let end = start + lines[start..].iter().position(|line| *line == "}")?;
+    let listed = lines[start + 1..end]
+        .iter()
+        .flat_map(|line| line.split(','))
+        .any(|name| name.trim() == entry);
+    if listed {
+        return None;
+    }
+
+    let line = format!("    {},", entry);
+    lines.insert(end, &line);
+    let mut runner = lines.join("\n");
+    runner.push('\n');
+    Some(runner)
+}
+
+struct Edit {
+    path: PathBuf,
+    contents: String,
+    /// None when the file didn't exist
+    previous: Option<String>,
+}
+
+/// Writes the files in order, puts every one of them back the way it was if one fails
+fn apply(edits: &[Edit]) -> io::Result<()> {
+    for (i, edit) in edits.iter().enumerate() {
+        let result = match edit.path.parent() {
+            Some(parent) => fs::create_dir_all(parent),
+            None => Ok(()),
+        }
+        .and_then(|_| fs::write(&edit.path, &edit.contents));
+
+        if let Err(err) = result {
+            for edit in edits[..=i].iter().rev() {
+                // best effort, the original error is the one worth reporting
+                let _ = match &edit.previous {
+                    Some(previous) => fs::write(&edit.path, previous),
+                    None => fs::remove_file(&edit.path),
+                };
+            }
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Creates `src/dayNN.rs`, declares it in `src/lib.rs`, lists it in `src/runner.rs`
+/// and creates an empty `input/2019/dayN.txt` when there isn't one. Returns every
+/// file it wrote. Nothing is written unless every edit applies, the module comes last
+/// and everything is rolled back if a write fails.
+pub fn scaffold(root: &Path, day: u32, template: Template) -> Result<Vec<PathBuf>, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::InvalidDay(day));
+    }
+
+    let module = module_name(day);
+    let source = root.join("src").join(format!("{}.rs", module));
+    if source.exists() {
+        return Err(ScaffoldError::ModuleExists(source));
+    }
+    let lib_path = root.join("src").join("lib.rs");
+    let lib = fs::read_to_string(&lib_path)?;
+    let new_lib = register_module(&lib, &module).ok_or(ScaffoldError::AlreadyRegistered(module))?;
+    let runner_path = root.join("src").join("runner.rs");
+    let runner = fs::read_to_string(&runner_path)?;
+    let new_runner = register_solution(&runner, day)
+        .ok_or_else(|| ScaffoldError::AlreadyRegistered(format!("day{}_part1", day)))?;
+
+    let mut edits = vec![
+        Edit {
+            path: lib_path,
+            contents: new_lib,
+            previous: Some(lib),
+        },
+        Edit {
+            path: runner_path,
+            contents: new_runner,
+            previous: Some(runner),
+        },
+    ];
+    let input = root
+        .join("input")
+        .join("2019")
+        .join(format!("day{}.txt", day));
+    if !input.exists() {
+        edits.push(Edit {
+            path: input,
+            contents: String::new(),
+            previous: None,
+        });
+    }
+    edits.push(Edit {
+        path: source,
+        contents: template.render(day),
+        previous: None,
+    });
+
+    apply(&edits)?;
+    Ok(edits.into_iter().map(|edit| edit.path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod compression;
+pub mod day01;
+pub mod day03;
+pub mod day03_extra;
+pub mod day05;
+
+pub mod intcode_computer;
+
+aoc_lib! { year = 2019 }
+";
+
+    const RUNNER: &str = "use crate::aoc_factory::*;
+
+solutions! {
+    day1_part1, day1_part1_map, day1_part2,
+    day3_part1,
+}
+";
+
+    #[test]
+    fn test_register_module() {
+        let lib = register_module(LIB, "day04").unwrap();
+        assert!(lib.contains("pub mod day03_extra;\npub mod day04;\npub mod day05;\n"));
+        let lib = register_module(LIB, "day02").unwrap();
+        assert!(lib.contains("pub mod day01;\npub mod day02;\npub mod day03;\n"));
+        let lib = register_module(LIB, "day25").unwrap();
+        assert!(lib.contains("pub mod day05;\npub mod day25;\n\npub mod intcode_computer;"));
+        assert!(lib.ends_with("aoc_lib! { year = 2019 }\n"));
+
+        assert_eq!(register_module(LIB, "day03"), None);
+    }
+
+    #[test]
+    fn test_register_solution() {
+        let runner = register_solution(RUNNER, 4).unwrap();
+        assert!(runner.ends_with("    day3_part1,\n    day4_part1,\n}\n"));
+        assert_eq!(register_solution(RUNNER, 1), None);
+        assert_eq!(register_solution(RUNNER, 3), None);
+        assert_eq!(register_solution("fn main() {}", 4), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let source = Template::Intcode.render(9);
+        assert!(source.contains("#[aoc_generator(day9)]"));
+        assert!(source.contains("fn test_day9_part1()"));
+        assert!(!source.contains(PLACEHOLDER));
+
+        // rustfmt style, like every other day
+        for template in [Template::Plain, Template::Intcode].iter() {
+            for line in template.source().lines() {
+                let indent = line.len() - line.trim_start().len();
+                assert_eq!(indent % 4, 0, "{:?}", line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = std::env::temp_dir().join(format!("scaffold_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/runner.rs"), RUNNER).unwrap();
+
+        let written = scaffold(&root, 4, Template::Plain).unwrap();
+        assert_eq!(written.len(), 4);
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod day04;"));
+        assert_eq!(
+            fs::read_to_string(root.join("input/2019/day4.txt")).unwrap(),
+            ""
+        );
+
+        match scaffold(&root, 4, Template::Intcode) {
+            Err(ScaffoldError::ModuleExists(path)) => assert!(path.ends_with("src/day04.rs")),
+            result => panic!("{:?}", result),
+        }
+        match scaffold(&root, 3, Template::Plain) {
+            Err(ScaffoldError::AlreadyRegistered(module)) => assert_eq!(module, "day03"),
+            result => panic!("{:?}", result),
+        }
+        assert!(!root.join("src/day03.rs").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_scaffold_rollback() {
+        let root = std::env::temp_dir().join(format!("scaffold_rollback_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/runner.rs"), RUNNER).unwrap();
+        // the input directory can't be created
+        fs::write(root.join("input"), "").unwrap();
+
+        match scaffold(&root, 4, Template::Plain) {
+            Err(ScaffoldError::Io(_)) => (),
+            result => panic!("{:?}", result),
+        }
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), LIB);
+        assert_eq!(
+            fs::read_to_string(root.join("src/runner.rs")).unwrap(),
+            RUNNER
+        );
+        assert!(!root.join("src/day04.rs").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,14 +1,14 @@
 #[aoc(dayX, part1)]
-pub fn part1(input: &str) -> i32 {
-  0
+pub fn part1(input: &str) -> usize {
+    input.lines().count()
 }
 
 #[cfg(test)]
 mod tests {
-  use super::part1;
+    use super::part1;
 
-  #[test]
-  fn test_dayX_part1() {
-    assert_eq!(part1(""), 0);
-  }
+    #[test]
+    fn test_dayX_part1() {
+        assert_eq!(part1("1\n2\n3"), 3);
+    }
 }
@@ -0,0 +1,24 @@
+use crate::intcode_computer::{parse_input, CPU, SIZE};
+
+#[aoc_generator(dayX)]
+fn generator_input(input: &str) -> Vec<SIZE> {
+    parse_input(input)
+}
+
+#[aoc(dayX, part1)]
+pub fn part1(input: &[SIZE]) -> Option<SIZE> {
+    let mut cpu = CPU::new(input.to_owned());
+    cpu.run();
+    cpu.output.last().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::part1;
+
+    #[test]
+    fn test_dayX_part1() {
+        assert_eq!(part1(&[104, 7, 99]), Some(7));
+        assert_eq!(part1(&[99]), None);
+    }
+}